@@ -31,6 +31,7 @@ end
 ---@class Config
 ---@field attach boolean Auto-attach to LSP servers (default: true)
 ---@field servers string[] LSP server names to translate diagnostics for
+---@field structured boolean Use structured LSP diagnostics instead of rendered text (default: false)
 
 -- All of this is stolen from the goat @dmmulroy
 local function get_lsp_client_name_by_id(id)
@@ -70,18 +71,29 @@ local function setup_diagnostic_handler(opts)
             code = "TS" .. code
           end
           
-          -- Get enhanced diagnostic from ts-analyzer in LSP mode
-          local enhanced = runner.format_diagnostic(
-            filepath,
-            line,
-            column,
-            code,
-            diag.message
-          )
-
-          if enhanced then
-            -- Replace the diagnostic message with the enhanced one
-            diag.message = enhanced
+          if opts.structured then
+            -- Merge the structured diagnostic instead of replacing the message with rendered text
+            local structured = runner.lsp_diagnostic(filepath, line, column, code, diag.message)
+            if structured then
+              diag.message = structured.message
+              diag.relatedInformation = structured.relatedInformation
+              diag.codeDescription = diag.codeDescription or structured.codeDescription
+              diag.data = v.tbl_extend("force", diag.data or {}, { ts_analyzer = structured.data })
+            end
+          else
+            -- Get enhanced diagnostic from ts-analyzer in LSP mode
+            local enhanced = runner.format_diagnostic(
+              filepath,
+              line,
+              column,
+              code,
+              diag.message
+            )
+
+            if enhanced then
+              -- Replace the diagnostic message with the enhanced one
+              diag.message = enhanced
+            end
           end
         end
       end
@@ -99,7 +111,9 @@ local DEF_OPTS = {
   servers = {
     "ts_ls",
     "vtsls"
-  }
+  },
+  -- keep the LSP diagnostic structure and add suggestions to it instead of box-drawn text
+  structured = false,
 }
 
 -- @type Config
@@ -115,7 +129,7 @@ function M.setup(opts)
   ensure_binary()
 
   if M.config.attach then
-    local diag_cfg = { servers = M.config.servers }
+    local diag_cfg = { servers = M.config.servers, structured = M.config.structured }
     setup_diagnostic_handler(diag_cfg)
   end
 end
@@ -64,4 +64,41 @@ function M.format_diagnostic(filepath, line, column, code, message)
   return result ~= "" and result or nil
 end
 
+---Run ts-analyzer in LSP mode and return a structured LSP Diagnostic
+---@param filepath string The path to the TypeScript file
+---@param line number Line number (1-indexed)
+---@param column number Column number (1-indexed)
+---@param code string Error code (e.g., "TS2322")
+---@param message string Error message
+---@return table|nil LSP Diagnostic or nil on error
+function M.lsp_diagnostic(filepath, line, column, code, message)
+  if not filepath or filepath == "" then
+    return nil
+  end
+
+  if not bin or vim.fn.filereadable(bin) ~= 1 then
+    local error_msg = bin and ("ts-analyzer binary not found at: " .. bin) or "ts-analyzer binary path could not be determined"
+    vim.notify(error_msg, vim.log.levels.WARN)
+    return nil
+  end
+
+  local result = vim.fn.system({
+    bin,
+    "--from-lsp",
+    "--format", "lsp",
+    "--file", filepath,
+    "--line", tostring(line),
+    "--column", tostring(column),
+    "--code", code,
+    "--message", message,
+  })
+
+  if vim.v.shell_error ~= 0 or result == "" then
+    return nil
+  end
+
+  local ok, decoded = pcall(vim.json.decode, result)
+  return ok and decoded or nil
+end
+
 return M
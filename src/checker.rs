@@ -0,0 +1,423 @@
+use std::{
+    path::{
+        Path,
+        PathBuf,
+    },
+    str::FromStr,
+};
+
+use crate::error::{
+    self,
+    parser::{
+        Dialect,
+        Parser,
+    },
+    relative_to_cwd,
+};
+
+/// Options the output can't be parsed without, extra arguments can't override them
+const REQUIRED: [&str; 2] = ["pretty", "noemit"];
+
+/// Type checker backend that produces the diagnostics
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Checker {
+    /// The TypeScript compiler
+    #[default]
+    Tsc,
+    /// The native TypeScript preview, `@typescript/native-preview`
+    Tsgo,
+    /// vue-tsc, checks the script blocks of `.vue` single-file components as well
+    VueTsc,
+    /// `deno check <entry>`, the entry point is the file argument
+    Deno,
+    /// Any command that prints tsc style output, split on whitespace: `custom:pnpm exec tsc`
+    Custom(String),
+}
+
+impl FromStr for Checker {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tsc" => Ok(Checker::Tsc),
+            "tsgo" => Ok(Checker::Tsgo),
+            "vue-tsc" => Ok(Checker::VueTsc),
+            "deno" => Ok(Checker::Deno),
+            _ => match s.strip_prefix("custom:").map(str::trim) {
+                Some(command) if !command.is_empty() => Ok(Checker::Custom(command.to_string())),
+                Some(_) => Err("custom checker needs a command, e.g. `custom:npx tsc`".to_string()),
+                None => Err(format!(
+                    "unknown checker `{}`, expected tsc, tsgo, vue-tsc, deno or custom:<cmd>",
+                    s
+                )),
+            },
+        }
+    }
+}
+
+impl Checker {
+    /// The program to run and the arguments that are part of the checker itself
+    pub fn command(&self) -> (String, Vec<String>) {
+        match self {
+            Checker::Tsc => ("tsc".to_string(), Vec::new()),
+            Checker::Tsgo => ("tsgo".to_string(), Vec::new()),
+            Checker::VueTsc => ("vue-tsc".to_string(), Vec::new()),
+            Checker::Deno => ("deno".to_string(), vec!["check".to_string()]),
+            Checker::Custom(command) => {
+                let mut words = command.split_whitespace().map(String::from);
+                let program = words.next().unwrap_or_default();
+                (program, words.collect())
+            }
+        }
+    }
+
+    /// Find the executable of `program` as returned by `command`. `tsc_path` takes precedence,
+    /// otherwise node based checkers are looked up in `node_modules/.bin` of the working directory
+    /// and its parents, which also finds compilers hoisted to the root of a workspace. `PATH` is
+    /// the last resort.
+    pub fn locate(&self, program: &str, tsc_path: Option<&str>) -> anyhow::Result<String> {
+        if let Checker::Custom(_) = self {
+            if tsc_path.is_some() {
+                anyhow::bail!(
+                    "--tsc-path can't be used with a custom checker, put the path in the command"
+                );
+            }
+            // The command is run as given
+            return Ok(program.to_string());
+        }
+
+        if let Some(path) = tsc_path {
+            if !Path::new(path).is_file() {
+                anyhow::bail!("--tsc-path `{}` does not exist", path);
+            }
+            // A bare name would be looked up on PATH instead of in the working directory
+            let path = std::fs::canonicalize(path)?;
+            return Ok(path.to_string_lossy().into_owned());
+        }
+
+        let cwd = std::env::current_dir()?;
+        if *self != Checker::Deno
+            && let Some(path) = find_in_node_modules(&cwd, program)
+        {
+            return Ok(relative_to_cwd(&path.to_string_lossy()));
+        }
+        if let Some(path) = find_in_path(program) {
+            return Ok(path.to_string_lossy().into_owned());
+        }
+
+        if *self == Checker::Deno {
+            anyhow::bail!("Could not find `deno` on PATH, install Deno or pass --tsc-path");
+        }
+        anyhow::bail!(
+            "Could not find `{0}`: there is no node_modules/.bin/{0} in {1} or any of its parents \
+             and `{0}` is not on PATH. Install it in the project or pass --tsc-path",
+            program,
+            cwd.display()
+        )
+    }
+
+    /// Arguments passed when checking the project in the current directory
+    pub fn default_args(&self) -> Vec<String> {
+        match self {
+            Checker::Tsc | Checker::VueTsc => vec![
+                "--pretty",
+                "false",
+                "--diagnostics",
+                "--extendedDiagnostics",
+                "--noEmit",
+                "--preserveWatchOutput",
+                "false",
+            ],
+            // tsgo doesn't support the performance diagnostics (yet), it rejects them
+            Checker::Tsgo => vec!["--pretty", "false", "--noEmit"],
+            // The command decides, deno checks the entry point given as file argument
+            Checker::Deno | Checker::Custom(_) => Vec::new(),
+        }
+        .into_iter()
+        .map(String::from)
+        .collect()
+    }
+
+    /// Arguments passed when checking files outside of a project. tsc ignores tsconfig.json when it
+    /// is given files, so the strictness options have to be passed explicitly.
+    pub fn file_args(&self, files: &[String]) -> Vec<String> {
+        let mut args = files.to_vec();
+        args.extend(self.default_args());
+        if self.uses_tsconfig() {
+            args.extend(["--noUnusedLocals", "--noUnusedParameters", "--strict"].map(String::from));
+        }
+        args
+    }
+
+    /// Arguments for checking the project of a specific tsconfig, `None` when the checker can't be
+    /// pointed at one
+    pub fn project_args(&self, project: &str) -> Option<Vec<String>> {
+        match self {
+            Checker::Tsc | Checker::Tsgo | Checker::VueTsc => {
+                let mut args = vec!["-p".to_string(), project.to_string()];
+                args.extend(self.default_args());
+                Some(args)
+            }
+            Checker::Deno | Checker::Custom(_) => None,
+        }
+    }
+
+    /// Arguments for `--build` mode, `None` when the checker has no build mode
+    pub fn build_args(&self, project: &str) -> Option<Vec<String>> {
+        match self {
+            // Build mode doesn't accept `--noEmit`, referenced projects have to emit their
+            // declarations for the projects that depend on them
+            Checker::Tsc | Checker::Tsgo | Checker::VueTsc => Some(
+                ["-b", project, "--verbose", "--pretty", "false"]
+                    .map(String::from)
+                    .to_vec(),
+            ),
+            Checker::Deno | Checker::Custom(_) => None,
+        }
+    }
+
+    /// Whether the checker understands `--listFiles`
+    pub fn lists_files(&self) -> bool {
+        matches!(self, Checker::Tsc | Checker::Tsgo | Checker::VueTsc)
+    }
+
+    /// Whether the checker reads its options from tsconfig.json
+    pub fn uses_tsconfig(&self) -> bool {
+        matches!(self, Checker::Tsc | Checker::Tsgo | Checker::VueTsc)
+    }
+
+    /// The parser for the output of the checker
+    pub fn parser(&self) -> Parser {
+        match self {
+            Checker::Tsc | Checker::Tsgo | Checker::VueTsc => Parser::with_dialect(Dialect::Tsc),
+            Checker::Deno => Parser::with_dialect(Dialect::Deno),
+            // Wrappers may run anything, detect the format per diagnostic
+            Checker::Custom(_) => Parser::with_dialect(Dialect::Any),
+        }
+    }
+}
+
+/// Merge the compiler arguments given after `--` into the generated ones. An option given in both
+/// replaces the generated one, unless it is required: then duplicates are dropped and conflicting
+/// values are returned as ignored.
+pub fn merge_args(args: Vec<String>, extra: &[String]) -> (Vec<String>, Vec<String>) {
+    let mut merged = group(&args);
+    let mut ignored = Vec::new();
+
+    for (name, tokens) in group(extra) {
+        let existing = name
+            .as_ref()
+            .and_then(|name| merged.iter().position(|(n, _)| n.as_ref() == Some(name)));
+
+        match (name, existing) {
+            (Some(name), Some(i)) if REQUIRED.contains(&name.as_str()) => {
+                if option_value(&tokens) != option_value(&merged[i].1) {
+                    ignored.push(tokens.join(" "));
+                }
+            }
+            (name, Some(i)) => merged[i] = (name, tokens),
+            (name, None) => merged.push((name, tokens)),
+        }
+    }
+
+    (
+        merged.into_iter().flat_map(|(_, tokens)| tokens).collect(),
+        ignored,
+    )
+}
+
+/// Split arguments into options with their value, keyed by the option name. Positional arguments
+/// such as files have no name.
+fn group(args: &[String]) -> Vec<(Option<String>, Vec<String>)> {
+    let mut groups: Vec<(Option<String>, Vec<String>)> = Vec::new();
+
+    for arg in args {
+        if arg.starts_with('-') {
+            groups.push((Some(option_name(arg)), vec![arg.clone()]));
+        } else if let Some((Some(_), tokens)) = groups.last_mut()
+            && tokens.len() == 1
+            && !error::is_source_file(arg)
+        {
+            tokens.push(arg.clone());
+        } else {
+            groups.push((None, vec![arg.clone()]));
+        }
+    }
+
+    groups
+}
+
+/// tsc options are case insensitive and some have a short alias
+fn option_name(arg: &str) -> String {
+    let name = arg.trim_start_matches('-').to_ascii_lowercase();
+    match name.as_str() {
+        "p" => "project".to_string(),
+        "b" => "build".to_string(),
+        "w" => "watch".to_string(),
+        "t" => "target".to_string(),
+        "m" => "module".to_string(),
+        "d" => "declaration".to_string(),
+        _ => name,
+    }
+}
+
+/// A flag without a value is switched on
+fn option_value(tokens: &[String]) -> String {
+    tokens
+        .get(1)
+        .map(|value| value.to_ascii_lowercase())
+        .unwrap_or_else(|| "true".to_string())
+}
+
+/// Names an executable may have on this platform
+fn executable_names(program: &str) -> Vec<String> {
+    if cfg!(windows) {
+        vec![
+            format!("{}.cmd", program),
+            format!("{}.exe", program),
+            program.to_string(),
+        ]
+    } else {
+        vec![program.to_string()]
+    }
+}
+
+/// Walk up from `dir` looking for `node_modules/.bin/<program>`
+fn find_in_node_modules(dir: &Path, program: &str) -> Option<PathBuf> {
+    dir.ancestors().find_map(|dir| {
+        let bin = dir.join("node_modules").join(".bin");
+        executable_names(program)
+            .into_iter()
+            .map(|name| bin.join(name))
+            .find(|path| path.is_file())
+    })
+}
+
+fn find_in_path(program: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).find_map(|dir| {
+        executable_names(program)
+            .into_iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+    })
+}
+
+/// The version the checker reports for itself, e.g. `5.6.3` from `Version 5.6.3`. `program` is
+/// the executable found by `Checker::locate`, custom commands may be wrappers like `pnpm exec tsc`
+/// so their version is unknown.
+pub fn version(checker: &Checker, program: &str) -> Option<String> {
+    if let Checker::Custom(_) = checker {
+        return None;
+    }
+
+    let output = std::process::Command::new(program)
+        .arg("--version")
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = stdout.lines().next()?.trim();
+    let version = line.strip_prefix("Version ").unwrap_or(line);
+    (!version.is_empty()).then(|| version.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_checker() {
+        assert_eq!("tsgo".parse(), Ok(Checker::Tsgo));
+        assert_eq!(
+            "custom:pnpm exec tsc"
+                .parse::<Checker>()
+                .map(|c| c.command()),
+            Ok((
+                "pnpm".to_string(),
+                vec!["exec".to_string(), "tsc".to_string()]
+            ))
+        );
+        assert!("custom:".parse::<Checker>().is_err());
+        assert!("swc".parse::<Checker>().is_err());
+    }
+
+    #[test]
+    fn test_find_hoisted_compiler() {
+        let root = std::env::temp_dir().join(format!("ts-analyzer-hoisted-{}", std::process::id()));
+        let package = root.join("packages").join("app");
+        let bin = root.join("node_modules").join(".bin");
+        std::fs::create_dir_all(&package).unwrap();
+        std::fs::create_dir_all(&bin).unwrap();
+        for name in executable_names("tsc") {
+            std::fs::write(bin.join(name), "").unwrap();
+        }
+
+        let found = find_in_node_modules(&package, "tsc");
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(found.map(|path| path.starts_with(&bin)), Some(true));
+    }
+
+    #[test]
+    fn test_merge_args() {
+        let args = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+
+        let (merged, ignored) = merge_args(
+            Checker::Tsc.project_args("tsconfig.app.json").unwrap(),
+            &args(
+                "--noUncheckedIndexedAccess --noemit --pretty true --preserveWatchOutput \
+                 --project tsconfig.test.json extra.ts",
+            ),
+        );
+
+        assert_eq!(
+            merged,
+            args(
+                "--project tsconfig.test.json --pretty false --diagnostics --extendedDiagnostics \
+                 --noEmit --preserveWatchOutput --noUncheckedIndexedAccess extra.ts"
+            )
+        );
+        assert_eq!(ignored, vec!["--pretty true".to_string()]);
+    }
+
+    #[test]
+    fn test_custom_checker_has_no_version() {
+        let checker: Checker = "custom:pnpm exec tsc".parse().unwrap();
+
+        assert_eq!(version(&checker, "pnpm"), None);
+    }
+
+    #[test]
+    fn test_custom_checker_rejects_tsc_path() {
+        let checker: Checker = "custom:pnpm exec tsc".parse().unwrap();
+
+        assert!(
+            checker
+                .locate("pnpm", Some("node_modules/.bin/tsc"))
+                .is_err()
+        );
+        assert_eq!(checker.locate("pnpm", None).unwrap(), "pnpm");
+    }
+
+    #[test]
+    fn test_tsc_path_is_absolute() {
+        let path = std::env::temp_dir().join(format!("ts-analyzer-tsc-{}", std::process::id()));
+        std::fs::write(&path, "").unwrap();
+
+        let located = Checker::Tsc.locate("tsc", Some(&path.to_string_lossy()));
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(Path::new(&located.unwrap()).is_absolute());
+        assert!(Checker::Tsc.locate("tsc", Some("does-not-exist")).is_err());
+    }
+
+    #[test]
+    fn test_tsgo_skips_performance_diagnostics() {
+        let args = Checker::Tsgo.file_args(&["index.ts".to_string()]);
+
+        assert_eq!(args[0], "index.ts");
+        assert!(args.contains(&"--noEmit".to_string()));
+        assert!(!args.contains(&"--diagnostics".to_string()));
+        assert!(!args.contains(&"--extendedDiagnostics".to_string()));
+    }
+}
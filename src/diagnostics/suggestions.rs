@@ -91,11 +91,32 @@ impl ErrorDiagnostic for ErrorCode {
             ErrorCode::TypesOfPropertyAreIncompatible => {
                 suggest_types_of_property_are_incompatible(err)
             }
-            ErrorCode::Unsupported(_) => None,
+            ErrorCode::Lint(_) => suggest_lint_fix(err),
+            ErrorCode::Unsupported(_) | ErrorCode::Svelte(_) => None,
         }
     }
 }
 
+/// Suggestion for lint problems that come with an autofix
+fn suggest_lint_fix(err: &TsError) -> Option<Suggestion> {
+    let fix = err.fix.as_ref()?;
+
+    let suggestion = if fix.text.is_empty() {
+        "Autofix available: remove this code.".to_string()
+    } else {
+        format!(
+            "Autofix available: replace with `{}`",
+            fix.text.green().bold()
+        )
+    };
+
+    Some(Suggestion {
+        suggestions: vec![suggestion],
+        help:        Some("Run `eslint --fix` to apply the autofix.".to_string()),
+        span:        None,
+    })
+}
+
 /// Suggestion for when types of a property are incompatible between source and target
 fn suggest_types_of_property_are_incompatible(err: &TsError) -> Option<Suggestion> {
     let property = extract_first_quoted(&err.message)?;
@@ -202,7 +223,7 @@ fn suggest_duplicate_fn_decl(err: &TsError, tokens: &[Token]) -> Option<Suggesti
         help:        Some(format!(
             "Consider renaming or removing the duplicate declaration of `{}` on line {}.",
             fn_name.red().bold(),
-            err.line
+            err.line()
         )),
         span:        None,
     })
@@ -228,7 +249,7 @@ fn suggest_cannot_find_reference(err: &TsError) -> Option<Suggestion> {
 
 /// Suggestion for unexpected keyword or identifier
 fn suggest_unexpected_kw_or_identifier(err: &TsError, tokens: &[Token]) -> Option<Suggestion> {
-    let keyword = find_token_at_position(tokens, err.line, err.column)?;
+    let keyword = find_token_at_position(tokens, err.line(), err.column())?;
 
     Some(Suggestion {
         suggestions: vec![format!(
@@ -303,12 +324,9 @@ fn suggest_element_implicit_any_invalid_index_type_for_object(err: &TsError) ->
             implicit_type.red().bold()
         )],
         help:        Some(format!(
-            "Consider declaring the index with `{}` or loosen the type of `{}` to allow indexing with `{}`.",
-            format!(
-                "{} {}",
-                "keyof typeof".yellow().bold(),
-                object_to_index.yellow().bold()
-            ),
+            "Consider declaring the index with `{} {}` or loosen the type of `{}` to allow indexing with `{}`.",
+            "keyof typeof".yellow().bold(),
+            object_to_index.yellow().bold(),
             object_to_index.yellow().bold(),
             index_type.yellow().bold()
         )),
@@ -864,8 +882,8 @@ fn suggest_invalid_index_type(err: &TsError) -> Option<Suggestion> {
 }
 
 fn suggest_invalid_index_signature(err: &TsError, tokens: &[Token]) -> Option<Suggestion> {
-    let adjusted_column = err.column.saturating_sub(1);
-    let token = find_token_at_position(tokens, err.line, adjusted_column);
+    let adjusted_column = err.column().saturating_sub(1);
+    let token = find_token_at_position(tokens, err.line(), adjusted_column);
     let span_text = token.map(|t| t.raw.clone())?;
     let span = token.map(|t| t.start..t.end)?;
 
@@ -994,7 +1012,7 @@ fn suggest_unique_members() -> Option<Suggestion> {
 }
 
 fn suggest_uninitialized_const(err: &TsError, tokens: &[Token]) -> Option<Suggestion> {
-    let (name, span) = find_identifier_after_keyword(tokens, err.line, "const")?;
+    let (name, span) = find_identifier_after_keyword(tokens, err.line(), "const")?;
 
     Some(Suggestion {
         suggestions: vec![format!("`{}` must be initialized", name.red().bold())],
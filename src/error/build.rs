@@ -0,0 +1,141 @@
+use std::path::Path;
+
+use super::relative_to_cwd;
+
+/// A progress line of `tsc --build --verbose`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildEvent<'a> {
+    /// `Project 'packages/core/tsconfig.json' is out of date because ...`
+    OutOfDate(&'a str),
+    /// `Building project '/repo/packages/core/tsconfig.json'...`
+    Building(&'a str),
+}
+
+impl<'a> BuildEvent<'a> {
+    pub fn project(&self) -> &'a str {
+        match self {
+            BuildEvent::OutOfDate(project) | BuildEvent::Building(project) => project,
+        }
+    }
+}
+
+/// Parse a progress line, with or without the leading timestamp
+pub fn parse(line: &str) -> Option<BuildEvent<'_>> {
+    let line = strip_timestamp(line);
+
+    if let Some(rest) = line.strip_prefix("Building project '") {
+        let (project, _) = rest.rsplit_once("'...")?;
+        return Some(BuildEvent::Building(project));
+    }
+
+    let rest = line.strip_prefix("Project '")?;
+    let (project, _) = rest.split_once("' is out of date")?;
+    Some(BuildEvent::OutOfDate(project))
+}
+
+/// Drop the `[12:00:00 AM] ` (`--pretty`) or `12:00:00 AM - ` (`--pretty false`) prefix
+pub(super) fn strip_timestamp(line: &str) -> &str {
+    let prefix = match line.strip_prefix('[') {
+        Some(rest) => rest.split_once("] "),
+        None => line.split_once(" - "),
+    };
+    match prefix {
+        Some((time, rest)) if is_timestamp(time) => rest,
+        _ => line,
+    }
+}
+
+/// `12:00:00 AM` or, depending on the locale, `23:59:59`
+fn is_timestamp(time: &str) -> bool {
+    let (time, meridiem) = time.split_once(' ').unwrap_or((time, "AM"));
+    let parts: Vec<&str> = time.split(':').collect();
+
+    matches!(meridiem, "AM" | "PM")
+        && parts.len() == 3
+        && parts.iter().enumerate().all(|(i, part)| {
+            let len_ok = if i == 0 {
+                (1..=2).contains(&part.len())
+            } else {
+                part.len() == 2
+            };
+            len_ok && part.bytes().all(|b| b.is_ascii_digit())
+        })
+}
+
+/// Diagnostic paths in build output are relative to the project that reported them, resolve them
+/// against the directory of its tsconfig. `None` when the path doesn't point into the project.
+pub fn resolve(project: &str, file: &str) -> Option<String> {
+    let path = Path::new(file);
+    if path.is_absolute() {
+        return None;
+    }
+
+    let resolved = Path::new(project).parent()?.join(path);
+    resolved
+        .is_file()
+        .then(|| relative_to_cwd(&resolved.to_string_lossy()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_build_events() {
+        assert_eq!(
+            parse("12:00:00 AM - Building project '/repo/packages/core/tsconfig.json'..."),
+            Some(BuildEvent::Building("/repo/packages/core/tsconfig.json"))
+        );
+        assert_eq!(
+            parse(
+                "[12:00:00 AM] Project 'packages/app/tsconfig.json' is out of date because \
+                 output file 'packages/app/dist/index.js' does not exist"
+            ),
+            Some(BuildEvent::OutOfDate("packages/app/tsconfig.json"))
+        );
+        assert_eq!(parse("12:00:00 AM - Projects in this build: "), None);
+        assert_eq!(parse("    * packages/core/tsconfig.json"), None);
+    }
+
+    #[test]
+    fn test_strip_timestamp() {
+        assert_eq!(
+            strip_timestamp("9:05:00 PM - Found 0 errors."),
+            "Found 0 errors."
+        );
+        assert_eq!(
+            strip_timestamp("[23:59:59] Found 0 errors."),
+            "Found 0 errors."
+        );
+        assert_eq!(
+            strip_timestamp("Building project '/repo/a - b/tsconfig.json'..."),
+            "Building project '/repo/a - b/tsconfig.json'..."
+        );
+        assert_eq!(
+            strip_timestamp("[draft] Found 0 errors."),
+            "[draft] Found 0 errors."
+        );
+    }
+
+    #[test]
+    fn test_resolve() {
+        let root = std::env::temp_dir().join(format!("ts-analyzer-build-{}", std::process::id()));
+        let src = root.join("packages").join("core").join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("index.ts"), "").unwrap();
+        let project = root.join("packages").join("core").join("tsconfig.json");
+        let project = project.to_string_lossy();
+
+        let resolved = resolve(&project, "src/index.ts");
+        let missing = resolve(&project, "src/does-not-exist.ts");
+        let absolute = resolve(&project, &src.join("index.ts").to_string_lossy());
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            resolved,
+            Some(src.join("index.ts").to_string_lossy().into_owned())
+        );
+        assert_eq!(missing, None);
+        assert_eq!(absolute, None);
+    }
+}
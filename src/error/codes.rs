@@ -1,4 +1,6 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+use super::core::Severity;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ErrorCode {
     // type errors
     TypeMismatch,
@@ -71,7 +73,13 @@ pub enum ErrorCode {
     InvalidJsxConfigurationUmd,
 
     /// Catch-all for unsupported error codes
-    Unsupported(u16),
+    Unsupported(u32),
+
+    /// An ESLint rule, e.g. `no-unused-vars` or `@typescript-eslint/no-explicit-any`
+    Lint(String),
+
+    /// A code of the svelte compiler, e.g. `a11y-missing-attribute`
+    Svelte(String),
 }
 
 impl ErrorCode {
@@ -135,7 +143,7 @@ impl ErrorCode {
 
             other => {
                 if let Some(num_str) = other.strip_prefix("TS")
-                    && let Ok(num) = num_str.parse::<u16>()
+                    && let Ok(num) = num_str.parse::<u32>()
                 {
                     return ErrorCode::Unsupported(num);
                 }
@@ -144,6 +152,16 @@ impl ErrorCode {
         }
     }
 
+    /// Diagnostics about dead or unused code don't break anything and are reported as warnings
+    pub fn severity(&self) -> Severity {
+        match self {
+            ErrorCode::DeclaredButNeverUsed
+            | ErrorCode::ImportedButNeverUsed
+            | ErrorCode::UnreachableCode => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
     /// Create the strng representation like "TS2322" from an `ErrorCode`
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -201,7 +219,7 @@ impl ErrorCode {
             ErrorCode::JsxElementIsNotCallable => "TS2604",
             ErrorCode::InvalidJsxConfigurationUmd => "TS2686",
             ErrorCode::TypesOfPropertyAreIncompatible => "TS2326",
-            ErrorCode::Unsupported(_) => {
+            ErrorCode::Unsupported(_) | ErrorCode::Lint(_) | ErrorCode::Svelte(_) => {
                 // This will return a static string for known codes, but for unsupported codes,
                 // we return a dynamically allocated string. To keep the return type consistent,
                 // we can return a placeholder here.
@@ -213,6 +231,16 @@ impl ErrorCode {
 
 impl std::fmt::Display for ErrorCode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.as_str())
+        match self {
+            ErrorCode::Unsupported(num) if *num > 0 => write!(f, "TS{}", num),
+            ErrorCode::Lint(rule) | ErrorCode::Svelte(rule) => write!(f, "{}", rule),
+            _ => write!(f, "{}", self.as_str()),
+        }
+    }
+}
+
+impl serde::Serialize for ErrorCode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
     }
 }
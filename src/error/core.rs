@@ -1,9 +1,124 @@
+use std::ops::Range;
+
+use serde::Serialize;
+
+use super::codes::ErrorCode;
+
+/// Where a diagnostic points in the source
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Location {
+    pub file:   String,
+    pub line:   usize,
+    pub column: usize,
+}
+
+/// A replacement of a byte range in the source, e.g. an ESLint autofix
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Edit {
+    pub range: Range<usize>,
+    pub text:  String,
+}
+
 /// Represents a TypeScript error from the compiler
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct TsError {
-    pub file:    String,
-    pub line:    usize,
-    pub column:  usize,
-    pub code:    super::codes::ErrorCode,
-    pub message: String,
+    /// `None` for global diagnostics that aren't tied to a file, like config errors
+    #[serde(flatten)]
+    pub location: Option<Location>,
+    pub code:     ErrorCode,
+    pub message:  String,
+    pub severity: Severity,
+    /// The `tsc --build` project that reported the diagnostic, as the path of its tsconfig
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project:  Option<String>,
+    /// 1-indexed line and column the diagnostic ends at, only some checkers report it
+    #[serde(skip)]
+    pub end:      Option<(usize, usize)>,
+    /// Proposed edit that resolves the diagnostic
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix:      Option<Edit>,
+}
+
+impl TsError {
+    /// An error at a position in a file
+    pub fn new(
+        file: impl Into<String>, line: usize, column: usize, code: ErrorCode,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            location: Some(Location {
+                file: file.into(),
+                line,
+                column,
+            }),
+            severity: code.severity(),
+            code,
+            message: message.into(),
+            project: None,
+            end: None,
+            fix: None,
+        }
+    }
+
+    /// An error that isn't tied to a file
+    pub fn global(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            location: None,
+            severity: code.severity(),
+            code,
+            message: message.into(),
+            project: None,
+            end: None,
+            fix: None,
+        }
+    }
+
+    /// Use the category tsc reported, codes that are harmless by nature stay downgraded
+    pub fn with_category(mut self, category: Severity) -> Self {
+        self.severity = category.min(self.code.severity());
+        self
+    }
+
+    pub fn file(&self) -> Option<&str> {
+        self.location.as_ref().map(|loc| loc.file.as_str())
+    }
+
+    /// 1-indexed line, 0 for global diagnostics
+    pub fn line(&self) -> usize {
+        self.location.as_ref().map_or(0, |loc| loc.line)
+    }
+
+    /// 1-indexed column, 0 for global diagnostics
+    pub fn column(&self) -> usize {
+        self.location.as_ref().map_or(0, |loc| loc.column)
+    }
+}
+
+/// How severe a diagnostic is, ordered from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Message,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// Parse a tsc diagnostic category (`error`, `warning` or `message`)
+    pub fn from_category(category: &str) -> Option<Self> {
+        match category {
+            "error" => Some(Severity::Error),
+            "warning" => Some(Severity::Warning),
+            "message" => Some(Severity::Message),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Message => "message",
+        }
+    }
 }
@@ -0,0 +1,114 @@
+use super::{
+    ErrorCode,
+    Location,
+    Severity,
+    TsError,
+    relative_to_cwd,
+};
+
+/// Parse the header of a `deno check` diagnostic: `TS2322 [ERROR]: Type 'string' is not ...`. The
+/// first diagnostic is prefixed with `error: `. The location follows on a later `at` line, see
+/// `parse_location`.
+pub fn parse_header(line: &str) -> Option<TsError> {
+    let line = line.strip_prefix("error: ").unwrap_or(line);
+    let (code, rest) = line.split_once(" [")?;
+    let (category, msg) = rest.split_once("]: ")?;
+
+    let digits = code.strip_prefix("TS")?;
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let category = Severity::from_category(&category.to_ascii_lowercase())?;
+
+    Some(TsError::global(ErrorCode::from_str(code), msg).with_category(category))
+}
+
+/// Parse the `    at file:///home/dev/app/main.ts:1:7` line below the code frame
+pub fn parse_location(line: &str) -> Option<Location> {
+    let url = line.trim_start().strip_prefix("at ")?;
+    let (rest, column) = url.rsplit_once(':')?;
+    let (url, line) = rest.rsplit_once(':')?;
+
+    Some(Location {
+        file:   relative_to_cwd(&url_to_path(url)?),
+        line:   line.parse().ok()?,
+        column: column.parse().ok()?,
+    })
+}
+
+/// Convert a `file://` URL into a path, `file:///C:/app/main.ts` becomes `C:/app/main.ts`
+fn url_to_path(url: &str) -> Option<String> {
+    let path = url.strip_prefix("file://")?;
+    let path = match path.as_bytes() {
+        [b'/', drive, b':', ..] if drive.is_ascii_alphabetic() => &path[1..],
+        _ => path,
+    };
+    Some(percent_decode(path))
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(hex) = s.get(i + 1..i + 3)
+            && let Ok(byte) = u8::from_str_radix(hex, 16)
+        {
+            out.push(byte);
+            i += 3;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_header() {
+        let err = parse_header(
+            "error: TS2322 [ERROR]: Type 'string' is not assignable to type 'number'.",
+        )
+        .unwrap();
+        assert_eq!(err.code, ErrorCode::TypeMismatch);
+        assert_eq!(err.severity, Severity::Error);
+        assert_eq!(
+            err.message,
+            "Type 'string' is not assignable to type 'number'."
+        );
+
+        let warning = parse_header("TS6133 [WARNING]: 'a' is declared but never used.").unwrap();
+        assert_eq!(warning.severity, Severity::Warning);
+
+        assert!(parse_header("error: Type checking failed.").is_none());
+        assert!(parse_header("const a = [1]: number;").is_none());
+    }
+
+    #[test]
+    fn test_parse_location() {
+        assert_eq!(
+            parse_location("    at file:///srv/my%20app/main.ts:12:7"),
+            Some(Location {
+                file:   "/srv/my app/main.ts".to_string(),
+                line:   12,
+                column: 7,
+            })
+        );
+        assert_eq!(
+            parse_location("    at file:///C:/app/main.ts:1:1").map(|loc| loc.file),
+            Some("C:/app/main.ts".to_string())
+        );
+        assert_eq!(
+            parse_location("    at https://deno.land/x/mod.ts:1:1"),
+            None
+        );
+        assert_eq!(parse_location("const at = 1;"), None);
+    }
+}
@@ -0,0 +1,160 @@
+use serde::Deserialize;
+
+use super::{
+    Edit,
+    ErrorCode,
+    Severity,
+    TsError,
+    relative_to_cwd,
+};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileResult {
+    file_path: String,
+    messages:  Vec<Message>,
+    /// Only present when the file has problems that weren't fixed
+    source:    Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Message {
+    /// `null` for parsing errors
+    rule_id:    Option<String>,
+    /// 1 for warnings, 2 for errors
+    severity:   u8,
+    message:    String,
+    line:       Option<usize>,
+    column:     Option<usize>,
+    end_line:   Option<usize>,
+    end_column: Option<usize>,
+    fix:        Option<Fix>,
+}
+
+#[derive(Deserialize)]
+struct Fix {
+    /// Offsets in UTF-16 code units, as JavaScript strings count them
+    range: (usize, usize),
+    text:  String,
+}
+
+/// Parse `eslint --format json` output into diagnostics, `None` when the output isn't ESLint JSON
+pub fn parse(buf: &str) -> Option<Vec<TsError>> {
+    let results: Vec<FileResult> = serde_json::from_str(buf.trim()).ok()?;
+
+    let mut errors = Vec::new();
+    for result in results {
+        if result.messages.is_empty() {
+            continue;
+        }
+
+        let file = relative_to_cwd(&result.file_path);
+        let src = result
+            .source
+            .or_else(|| std::fs::read_to_string(&result.file_path).ok())
+            .unwrap_or_default();
+
+        for message in result.messages {
+            let severity = match message.severity {
+                2 => Severity::Error,
+                1 => Severity::Warning,
+                _ => continue,
+            };
+            let rule = message.rule_id.unwrap_or_else(|| "eslint".to_string());
+
+            let mut err = TsError::new(
+                file.clone(),
+                message.line.unwrap_or(1),
+                message.column.unwrap_or(1),
+                ErrorCode::Lint(rule),
+                message.message,
+            )
+            .with_category(severity);
+            err.end = message.end_line.zip(message.end_column);
+            err.fix = message.fix.map(|fix| Edit {
+                range: byte_offset(&src, fix.range.0)..byte_offset(&src, fix.range.1),
+                text:  fix.text,
+            });
+            errors.push(err);
+        }
+    }
+
+    Some(errors)
+}
+
+/// Convert an offset in UTF-16 code units into a byte offset into `src`
+fn byte_offset(src: &str, utf16_offset: usize) -> usize {
+    let mut units = 0;
+    for (i, ch) in src.char_indices() {
+        if units >= utf16_offset {
+            return i;
+        }
+        units += ch.len_utf16();
+    }
+    // Unknown sources are assumed to be ASCII
+    if src.is_empty() {
+        utf16_offset
+    } else {
+        src.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_eslint_json() {
+        let buf = r#"[
+            {"filePath": "/nonexistent/clean.ts", "messages": [], "errorCount": 0},
+            {
+                "filePath": "src/index.ts",
+                "source": "const 😀 = 1; var a = 1;\n",
+                "messages": [
+                    {
+                        "ruleId": "no-var",
+                        "severity": 2,
+                        "message": "Unexpected var, use let or const instead.",
+                        "line": 1,
+                        "column": 15,
+                        "endLine": 1,
+                        "endColumn": 25,
+                        "fix": {"range": [14, 17], "text": "let"}
+                    },
+                    {
+                        "ruleId": null,
+                        "fatal": true,
+                        "severity": 1,
+                        "message": "Parsing error: Unexpected token",
+                        "line": 2,
+                        "column": 1
+                    }
+                ]
+            }
+        ]"#;
+
+        let errors = parse(buf).unwrap();
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].file(), Some("src/index.ts"));
+        assert_eq!(errors[0].code.to_string(), "no-var");
+        assert_eq!(errors[0].severity, Severity::Error);
+        assert_eq!(errors[0].end, Some((1, 25)));
+        assert_eq!(
+            errors[0].fix,
+            Some(Edit {
+                range: 16..19,
+                text:  "let".to_string(),
+            })
+        );
+        assert_eq!(errors[1].code, ErrorCode::Lint("eslint".to_string()));
+        assert_eq!(errors[1].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_parse_rejects_tsc_output() {
+        assert!(parse("src/a.ts(1,1): error TS2304: Cannot find name 'x'.").is_none());
+        assert!(parse("[12:00:00 AM] Starting compilation in watch mode...").is_none());
+    }
+}
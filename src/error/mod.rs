@@ -1,25 +1,286 @@
+pub mod build;
 pub mod codes;
 pub mod core;
+pub mod deno;
 pub mod diagnostics;
+pub mod eslint;
+pub mod parser;
+pub mod pretty;
+pub mod svelte;
+pub mod watch;
 
-pub use core::TsError;
+pub use core::{
+    Edit,
+    Location,
+    Severity,
+    TsError,
+};
 
 pub use diagnostics::ErrorDiagnostic;
 
 use super::ErrorCode;
 
-/// Parse a TSC error line
+/// Parse a TSC error line in `--pretty false` style: `file(line,col): error TSxxxx: message`.
+/// Warnings and messages (`file(line,col): warning TSxxxx: ...`) are parsed as well.
+///
+/// The location is anchored on the trailing `(line,col): <category> TS` pattern instead of the
+/// first `(`, so paths with parentheses (e.g. Next.js route groups), spaces, `@scope` segments and
+/// Windows drive letters are kept intact.
 pub fn parse(line: &str) -> Option<TsError> {
-    let (file, rest) = line.split_once('(')?;
-    let (coords, rest) = rest.split_once("): error ")?;
-    let (line_s, col_s) = coords.split_once(',')?;
+    for (idx, _) in line.match_indices("): ") {
+        let head = &line[..idx];
+        let Some(open) = head.rfind('(') else {
+            continue;
+        };
+        let Some((line_s, col_s)) = head[open + 1..].split_once(',') else {
+            continue;
+        };
+        let (Ok(line_num), Ok(column)) = (line_s.parse(), col_s.parse()) else {
+            continue;
+        };
+        let Some((category, code, msg)) = parse_category(&line[idx + "): ".len()..]) else {
+            continue;
+        };
+
+        let file = &head[..open];
+        if file.is_empty() {
+            return None;
+        }
+
+        return Some(
+            TsError::new(file, line_num, column, ErrorCode::from_str(code), msg)
+                .with_category(category),
+        );
+    }
+
+    None
+}
+
+/// Parse a diagnostic without a location: `error TS18003: No inputs were found in config file`
+pub fn parse_global(line: &str) -> Option<TsError> {
+    let (category, code, msg) = parse_category(line)?;
+    Some(TsError::global(ErrorCode::from_str(code), msg).with_category(category))
+}
+
+/// Split `error TS2322: message` into its category, code and message
+fn parse_category(s: &str) -> Option<(Severity, &str, &str)> {
+    let (category, rest) = s.split_once(' ')?;
+    let category = Severity::from_category(category)?;
     let (code, msg) = rest.split_once(": ")?;
 
-    Some(TsError {
-        file:    file.to_string(),
-        line:    usize::from_str_radix(line_s, 10).ok()?,
-        column:  usize::from_str_radix(col_s, 10).ok()?,
-        code:    ErrorCode::from_str(code),
-        message: msg.to_string(),
-    })
+    let digits = code.strip_prefix("TS")?;
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    Some((category, code, msg))
+}
+
+/// tsc prints absolute paths in some places while diagnostics are relative to the working directory
+pub fn relative_to_cwd(path: &str) -> String {
+    std::env::current_dir()
+        .ok()
+        .and_then(|cwd| {
+            std::path::Path::new(path)
+                .strip_prefix(cwd)
+                .ok()
+                .map(|p| p.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Whether `name` has the extension of a file tsc checks
+pub fn is_source_file(name: &str) -> bool {
+    const EXTENSIONS: [&str; 9] = [
+        ".ts", ".tsx", ".mts", ".cts", ".js", ".jsx", ".mjs", ".cjs", ".vue",
+    ];
+
+    EXTENSIONS.iter().any(|ext| name.ends_with(ext))
+}
+
+/// Parse a line of `tsc --listFiles` output, tsc lists absolute paths
+pub fn parse_listed_file(line: &str) -> Option<&str> {
+    if !std::path::Path::new(line).is_absolute() || line.contains("://") || line.contains(": ") {
+        return None;
+    }
+
+    is_source_file(line).then_some(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(line: &str) -> Option<(String, usize, usize, String)> {
+        parse(line).map(|err| {
+            (
+                err.file().unwrap_or_default().to_string(),
+                err.line(),
+                err.column(),
+                err.code.to_string(),
+            )
+        })
+    }
+
+    #[test]
+    fn test_parse_simple_line() {
+        let err = parse(
+            "src/index.ts(2,7): error TS2322: Type 'string' is not assignable to type 'number'.",
+        )
+        .unwrap();
+
+        assert_eq!(err.file(), Some("src/index.ts"));
+        assert_eq!(err.line(), 2);
+        assert_eq!(err.column(), 7);
+        assert_eq!(err.code, ErrorCode::TypeMismatch);
+        assert_eq!(
+            err.message,
+            "Type 'string' is not assignable to type 'number'."
+        );
+    }
+
+    #[test]
+    fn test_parse_route_group_paths() {
+        assert_eq!(
+            location(
+                "app/(marketing)/page.tsx(3,5): error TS2322: Type 'number' is not assignable to type 'string'."
+            ),
+            Some((
+                "app/(marketing)/page.tsx".to_string(),
+                3,
+                5,
+                "TS2322".to_string()
+            ))
+        );
+        assert_eq!(
+            location(
+                "app/(shop)/(checkout)/[id]/page.tsx(12,1): error TS2304: Cannot find name 'x'."
+            ),
+            Some((
+                "app/(shop)/(checkout)/[id]/page.tsx".to_string(),
+                12,
+                1,
+                "TS2304".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_absolute_and_spaced_paths() {
+        assert_eq!(
+            location("/home/ci/my project/src/index.ts(1,1): error TS1005: ';' expected."),
+            Some((
+                "/home/ci/my project/src/index.ts".to_string(),
+                1,
+                1,
+                "TS1005".to_string()
+            ))
+        );
+        assert_eq!(
+            location(
+                "node_modules/@scope/pkg/dist/index.d.ts(40,3): error TS2416: Property 'x' in type 'A' is not assignable to the same property in base type 'B'."
+            ),
+            Some((
+                "node_modules/@scope/pkg/dist/index.d.ts".to_string(),
+                40,
+                3,
+                "TS2416".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_windows_paths() {
+        assert_eq!(
+            location(
+                r"C:\Users\dev\app\src\main.ts(8,14): error TS2339: Property 'foo' does not exist on type 'Bar'."
+            ),
+            Some((
+                r"C:\Users\dev\app\src\main.ts".to_string(),
+                8,
+                14,
+                "TS2339".to_string()
+            ))
+        );
+        assert_eq!(
+            location(
+                r"D:\a\repo (old)\src\util.ts(2,10): error TS6133: 'y' is declared but its value is never read."
+            ),
+            Some((
+                r"D:\a\repo (old)\src\util.ts".to_string(),
+                2,
+                10,
+                "TS6133".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_message_with_parentheses() {
+        let err = parse("src/a.ts(4,9): error TS2554: Expected 2 arguments, but got 1. (see f(a, b): error TS0000)")
+            .unwrap();
+
+        assert_eq!(err.file(), Some("src/a.ts"));
+        assert_eq!(err.line(), 4);
+        assert_eq!(
+            err.message,
+            "Expected 2 arguments, but got 1. (see f(a, b): error TS0000)"
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_non_diagnostics() {
+        assert!(parse("Files:                         12").is_none());
+        assert!(parse("  Type 'string' is not assignable to type 'number'.").is_none());
+        assert!(parse("src/index.ts(a,b): error TS2322: nope").is_none());
+        assert!(parse("(1,1): error TS2322: no file").is_none());
+        assert!(parse("src/index.ts:2:7 - error TS2322: pretty style").is_none());
+    }
+
+    #[test]
+    fn test_parse_listed_file() {
+        assert_eq!(
+            parse_listed_file("/home/ci/app/src/index.ts"),
+            Some("/home/ci/app/src/index.ts")
+        );
+        assert_eq!(parse_listed_file("Files:  12"), None);
+        assert_eq!(parse_listed_file("  src/index.ts"), None);
+        assert_eq!(parse_listed_file("src/index.ts"), None);
+        assert_eq!(
+            parse_listed_file("Check file:///tmp/proj/src/index.ts"),
+            None
+        );
+        assert_eq!(
+            parse_listed_file("src/index.ts(1,1): error TS2322: x"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_categories() {
+        let warning = parse("src/a.ts(1,1): warning TS80001: File is a CommonJS module.").unwrap();
+        assert_eq!(warning.severity, Severity::Warning);
+
+        // Unused declarations stay warnings even though tsc reports them as errors
+        let unused =
+            parse("src/a.ts(1,7): error TS6133: 'x' is declared but its value is never read.")
+                .unwrap();
+        assert_eq!(unused.severity, Severity::Warning);
+
+        assert!(parse("src/a.ts(1,1): fatal TS1234: nope").is_none());
+    }
+
+    #[test]
+    fn test_parse_global() {
+        let err =
+            parse_global("error TS18003: No inputs were found in config file 'tsconfig.json'.")
+                .unwrap();
+
+        assert_eq!(err.location, None);
+        assert_eq!(err.code.to_string(), "TS18003");
+        assert_eq!(err.severity, Severity::Error);
+
+        assert!(parse_global("Found 2 errors in 2 files.").is_none());
+        assert!(parse_global("error: something else").is_none());
+    }
 }
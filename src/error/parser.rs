@@ -0,0 +1,467 @@
+use super::{
+    TsError,
+    build::{
+        self,
+        BuildEvent,
+    },
+    deno,
+    parse,
+    parse_global,
+    pretty,
+    relative_to_cwd,
+    svelte::{
+        self,
+        Record,
+    },
+};
+use crate::ansi;
+
+#[derive(Debug)]
+enum State {
+    /// Between diagnostics
+    Idle,
+    /// A `--pretty false` or global diagnostic, indented lines that follow are part of the message
+    Plain(TsError),
+    /// A `--pretty` diagnostic, indented lines up to the first blank line are part of the message
+    Pretty(TsError),
+    /// A `deno check` diagnostic, waiting for the `at` line with its location. The lines before it
+    /// are the elaboration of the message followed by the code frame.
+    Deno(TsError, Vec<String>),
+    /// The code frame and related information of a `--pretty` diagnostic
+    CodeFrame,
+}
+
+/// The output formats a `Parser` looks for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Dialect {
+    /// Anything below, for output of unknown origin like stdin
+    #[default]
+    Any,
+    /// tsc and compatible checkers, in `--pretty` or `--pretty false` style and `--build` mode
+    Tsc,
+    /// `deno check`
+    Deno,
+}
+
+/// Line based parser for tsc output in either `--pretty false` or `--pretty` style. The style is
+/// picked per diagnostic, so it doesn't need to be known up front. In `tsc --build --verbose`
+/// output diagnostics are attributed to the project that is being built.
+///
+/// `svelte-check --output machine-verbose` and `deno check` output are understood as well.
+#[derive(Debug)]
+pub struct Parser {
+    dialect:   Dialect,
+    state:     State,
+    /// The project currently being built
+    project:   Option<String>,
+    /// Every project mentioned in build output, in order of appearance
+    projects:  Vec<String>,
+    /// The svelte-check workspace its filenames are relative to
+    workspace: Option<String>,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self {
+            dialect:   Dialect::Any,
+            state:     State::Idle,
+            project:   None,
+            projects:  Vec::new(),
+            workspace: None,
+        }
+    }
+}
+
+impl Parser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A parser that only looks for the output format of a known checker
+    pub fn with_dialect(dialect: Dialect) -> Self {
+        Self {
+            dialect,
+            ..Self::default()
+        }
+    }
+
+    /// Feed the next line of output, returns a diagnostic once all of its lines have been seen
+    pub fn push(&mut self, line: &str) -> Option<TsError> {
+        let stripped;
+        let line = if line.contains('\x1b') {
+            stripped = ansi::strip(line);
+            stripped.as_str()
+        } else {
+            line
+        };
+
+        let tsc = self.accepts(Dialect::Tsc);
+
+        if tsc && let Some(event) = build::parse(line) {
+            let project = relative_to_cwd(event.project());
+            if !self.projects.contains(&project) {
+                self.projects.push(project.clone());
+            }
+            if let BuildEvent::Building(_) = event {
+                self.project = Some(project);
+            }
+            return self.replace(State::Idle);
+        }
+
+        // svelte-check isn't a checker of its own, its output only comes in through stdin or a log
+        match svelte::parse(line).filter(|_| self.dialect == Dialect::Any) {
+            Some(Record::Start(workspace)) => {
+                self.workspace = Some(workspace);
+                return self.replace(State::Idle);
+            }
+            Some(Record::Diagnostic(mut err)) => {
+                if let (Some(workspace), Some(location)) = (&self.workspace, &mut err.location) {
+                    let path = std::path::Path::new(workspace).join(&location.file);
+                    location.file = relative_to_cwd(&path.to_string_lossy());
+                }
+                return self.replace(State::Plain(err));
+            }
+            None => {}
+        }
+
+        if tsc && let Some(err) = parse(line) {
+            let err = self.attribute(err);
+            return self.replace(State::Plain(err));
+        }
+        if tsc && let Some(err) = pretty::parse(line) {
+            let err = self.attribute(err);
+            return self.replace(State::Pretty(err));
+        }
+        if self.accepts(Dialect::Deno)
+            && let Some(err) = deno::parse_header(line)
+        {
+            return self.replace(State::Deno(err, Vec::new()));
+        }
+        if tsc && let Some(err) = parse_global(line) {
+            let err = self.attribute(err);
+            return self.replace(State::Plain(err));
+        }
+
+        match &mut self.state {
+            State::Plain(err) if line.starts_with("  ") => {
+                append(err, line);
+                None
+            }
+            State::Plain(_) => self.replace(State::Idle),
+            State::Pretty(_) if line.trim().is_empty() => self.replace(State::CodeFrame),
+            State::Pretty(err) => {
+                append(err, line);
+                None
+            }
+            State::Deno(err, lines) => {
+                if let Some(location) = deno::parse_location(line) {
+                    err.location = Some(location);
+                    return self.replace(State::CodeFrame);
+                }
+                lines.push(line.to_string());
+                None
+            }
+            State::Idle | State::CodeFrame => None,
+        }
+    }
+
+    /// Signal the end of the output, returns the diagnostic that was still being collected
+    pub fn finish(&mut self) -> Option<TsError> {
+        self.replace(State::Idle)
+    }
+
+    /// Parse all diagnostics in a chunk of tsc output
+    pub fn parse_all(&mut self, buf: &str) -> Vec<TsError> {
+        let mut errors: Vec<TsError> = buf.lines().filter_map(|line| self.push(line)).collect();
+        errors.extend(self.finish());
+        errors
+    }
+
+    fn accepts(&self, dialect: Dialect) -> bool {
+        self.dialect == Dialect::Any || self.dialect == dialect
+    }
+
+    /// Projects seen in `tsc --build --verbose` output, empty outside of build mode
+    pub fn projects(&self) -> &[String] {
+        &self.projects
+    }
+
+    /// Tag the diagnostic with the project being built and resolve its path against the project
+    fn attribute(&self, mut err: TsError) -> TsError {
+        let Some(project) = &self.project else {
+            return err;
+        };
+
+        if let Some(location) = &mut err.location
+            && let Some(file) = build::resolve(project, &location.file)
+        {
+            location.file = file;
+        }
+        err.project = Some(project.clone());
+        err
+    }
+
+    fn replace(&mut self, state: State) -> Option<TsError> {
+        match std::mem::replace(&mut self.state, state) {
+            State::Plain(err) | State::Pretty(err) => Some(err),
+            State::Deno(err, lines) => Some(with_elaboration(err, &lines)),
+            State::Idle | State::CodeFrame => None,
+        }
+    }
+}
+
+fn append(err: &mut TsError, line: &str) {
+    err.message.push('\n');
+    err.message.push_str(line.trim());
+}
+
+/// Append the elaboration of a deno diagnostic, the code frame after it ends in a line with the
+/// `^` or `~~~` underline
+fn with_elaboration(mut err: TsError, lines: &[String]) -> TsError {
+    let elaboration = match lines.iter().rposition(|line| is_underline(line)) {
+        // The underline follows the source line
+        Some(underline) => &lines[..underline.saturating_sub(1)],
+        None => lines,
+    };
+    for line in elaboration.iter().filter(|line| !line.trim().is_empty()) {
+        append(&mut err, line);
+    }
+    err
+}
+
+fn is_underline(line: &str) -> bool {
+    let line = line.trim();
+    !line.is_empty() && line.chars().all(|c| c == '^' || c == '~')
+}
+
+/// Parse all diagnostics in a chunk of tsc output
+#[cfg(test)]
+pub fn parse_output(buf: &str) -> Vec<TsError> {
+    Parser::new().parse_all(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{
+        Severity,
+        codes::ErrorCode,
+    };
+
+    #[test]
+    fn test_parse_plain_output() {
+        let buf = "src/a.ts(1,7): error TS2322: Type '{ a: number; }' is not assignable to type \
+                   'Foo'.\n  Object literal may only specify known properties.\nsrc/b.ts(3,1): \
+                   error TS2304: Cannot find name 'x'.\nFiles:                         3\n";
+
+        let errors = parse_output(buf);
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0].message,
+            "Type '{ a: number; }' is not assignable to type 'Foo'.\nObject literal may only \
+             specify known properties."
+        );
+        assert_eq!(errors[1].file(), Some("src/b.ts"));
+        assert_eq!(errors[1].code, ErrorCode::CannotFindIdentifier);
+    }
+
+    #[test]
+    fn test_parse_pretty_output() {
+        let buf = "\x1b[96msrc/index.ts\x1b[0m:\x1b[93m2\x1b[0m:\x1b[93m7\x1b[0m - \
+                   \x1b[91merror\x1b[0m\x1b[90m TS2322: \x1b[0mType '{ a: number; }' is not \
+                   assignable to type 'Foo'.\n  Object literal may only specify known \
+                   properties.\n\n\x1b[7m2\x1b[0m const x: Foo = { a: 1, b: 2 };\n\x1b[7m \
+                   \x1b[0m \x1b[91m                        ~\x1b[0m\n\n  src/foo.ts:1:18\n    \
+                   \x1b[7m1\x1b[0m export type Foo = { a: number };\n    'Foo' is declared \
+                   here.\n\nsrc/other.ts:5:3 - error TS2304: Cannot find name 'y'.\n\n5   \
+                   y;\n    ~\n\n\nFound 2 errors in 2 files.\n\nErrors  Files\n     1  \
+                   src/index.ts:2\n     1  src/other.ts:5\n";
+
+        let errors = parse_output(buf);
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].file(), Some("src/index.ts"));
+        assert_eq!(errors[0].line(), 2);
+        assert_eq!(errors[0].column(), 7);
+        assert_eq!(
+            errors[0].message,
+            "Type '{ a: number; }' is not assignable to type 'Foo'.\nObject literal may only \
+             specify known properties."
+        );
+        assert_eq!(errors[1].file(), Some("src/other.ts"));
+        assert_eq!(errors[1].message, "Cannot find name 'y'.");
+    }
+
+    #[test]
+    fn test_push_returns_diagnostics_while_streaming() {
+        let mut parser = Parser::with_dialect(Dialect::Tsc);
+
+        // `--pretty false`: a diagnostic ends at the next unindented line
+        assert!(
+            parser
+                .push("src/a.ts(1,7): error TS2322: Type 'string' is not assignable to type 'Foo'.")
+                .is_none()
+        );
+        assert!(
+            parser
+                .push("  Types of property 'a' are incompatible.")
+                .is_none()
+        );
+        let first = parser.push("src/b.ts(3,1): error TS2304: Cannot find name 'x'.");
+        assert_eq!(first.unwrap().file(), Some("src/a.ts"));
+        let second = parser.push("Files:                         3");
+        assert_eq!(second.unwrap().file(), Some("src/b.ts"));
+
+        // `--pretty`: a diagnostic ends at the blank line before its code frame
+        assert!(
+            parser
+                .push("src/c.ts:2:7 - error TS2322: Type 'string' is not assignable to type 'Foo'.")
+                .is_none()
+        );
+        assert!(
+            parser
+                .push("  Types of property 'a' are incompatible.")
+                .is_none()
+        );
+        let third = parser.push("");
+        assert_eq!(third.unwrap().file(), Some("src/c.ts"));
+        assert!(parser.push("2 const c: Foo = { a: \"x\" };").is_none());
+        assert!(parser.push("        ~").is_none());
+        assert!(parser.finish().is_none());
+    }
+
+    #[test]
+    fn test_parse_global_and_warning_output() {
+        let buf = "error TS5023: Unknown compiler option 'foo'.\n\x1b[91merror\x1b[0m\x1b[90m \
+                   TS18003: \x1b[0mNo inputs were found in config file \
+                   'tsconfig.json'.\n\nsrc/a.ts(1,1): warning TS80001: File is a CommonJS \
+                   module.\n";
+
+        let errors = parse_output(buf);
+
+        assert_eq!(errors.len(), 3);
+        assert_eq!(errors[0].location, None);
+        assert_eq!(errors[0].code, ErrorCode::Unsupported(5023));
+        assert_eq!(errors[0].severity, Severity::Error);
+        assert_eq!(errors[1].code, ErrorCode::Unsupported(18003));
+        assert_eq!(
+            errors[1].message,
+            "No inputs were found in config file 'tsconfig.json'."
+        );
+        assert_eq!(errors[2].file(), Some("src/a.ts"));
+        assert_eq!(errors[2].code, ErrorCode::Unsupported(80001));
+        assert_eq!(errors[2].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_parse_build_output() {
+        let root =
+            std::env::temp_dir().join(format!("ts-analyzer-build-output-{}", std::process::id()));
+        let src = root.join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("index.ts"), "").unwrap();
+        std::fs::write(root.join("main.ts"), "").unwrap();
+        let core = src.join("tsconfig.json").to_string_lossy().into_owned();
+        let app = root.join("tsconfig.json").to_string_lossy().into_owned();
+        let buf = format!(
+            "12:00:00 AM - Projects in this build: \n    * {0}\n    * {1}\n\n12:00:00 AM - Project \
+             '{0}' is out of date because output file 'dist/main.js' does not exist\n\n12:00:00 \
+             AM - Building project '{0}'...\n\nindex.ts(1,1): error TS1005: ';' expected.\n12:00:00 \
+             AM - Project '{1}' is out of date because output file 'dist/index.js' does not \
+             exist\n\n12:00:00 AM - Building project '{1}'...\n\nmain.ts(2,3): error TS2304: \
+             Cannot find name 'x'.\n",
+            core, app
+        );
+
+        let mut parser = Parser::new();
+        let errors = parser.parse_all(&buf);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(parser.projects(), [core.as_str(), app.as_str()]);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0].file(),
+            Some(src.join("index.ts").to_string_lossy().as_ref())
+        );
+        assert_eq!(errors[0].project.as_deref(), Some(core.as_str()));
+        assert_eq!(
+            errors[1].file(),
+            Some(root.join("main.ts").to_string_lossy().as_ref())
+        );
+        assert_eq!(errors[1].project.as_deref(), Some(app.as_str()));
+    }
+
+    #[test]
+    fn test_parse_svelte_check_output() {
+        let cwd = std::env::current_dir().unwrap();
+        let buf = format!(
+            "1590680326283 START {:?}\n1590680326807 {{\"type\":\"ERROR\",\"filename\":\
+             \"src/App.svelte\",\"start\":{{\"line\":0,\"character\":6}},\"message\":\
+             \"Cannot find name 'x'.\",\"code\":2304,\"source\":\"ts\"}}\n1590680326807 \
+             COMPLETED 1 FILES 1 ERRORS 0 WARNINGS 1 FILES_WITH_PROBLEMS\n",
+            cwd.to_string_lossy()
+        );
+
+        let errors = parse_output(&buf);
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].file(), Some("src/App.svelte"));
+        assert_eq!(errors[0].line(), 1);
+        assert_eq!(errors[0].column(), 7);
+        assert_eq!(errors[0].code, ErrorCode::CannotFindIdentifier);
+    }
+
+    #[test]
+    fn test_parse_deno_output() {
+        let cwd = std::env::current_dir().unwrap();
+        let buf = format!(
+            "Check file://{0}/main.ts\nerror: TS2322 [ERROR]: Type 'string' is not assignable \
+             to type 'number'.\nconst a: number = \"x\";\n      ^\n    at file://{0}/main.ts:1:7\n\n\
+             TS2304 [ERROR]: Cannot find name 'y'.\ny;\n^\n    at file://{0}/lib/util.ts:3:1\n\n\
+             Found 2 errors.\n\nerror: Type checking failed.\n",
+            cwd.to_string_lossy()
+        );
+
+        let errors = parse_output(&buf);
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].file(), Some("main.ts"));
+        assert_eq!(errors[0].line(), 1);
+        assert_eq!(errors[0].column(), 7);
+        assert_eq!(errors[0].code, ErrorCode::TypeMismatch);
+        assert_eq!(errors[1].file(), Some("lib/util.ts"));
+        assert_eq!(errors[1].code, ErrorCode::CannotFindIdentifier);
+    }
+
+    #[test]
+    fn test_parse_deno_elaboration() {
+        let buf = "error: TS2322 [ERROR]: Type '{ a: string; }' is not assignable to type 'Foo'.\n  \
+                   Types of property 'a' are incompatible.\n    Type 'string' is not assignable to \
+                   type 'number'.\nconst foo: Foo = { a: \"x\" };\n      ~~~\n    at \
+                   file:///app/main.ts:3:7\n";
+
+        let errors = Parser::with_dialect(Dialect::Deno).parse_all(buf);
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].file(), Some("/app/main.ts"));
+        assert_eq!(
+            errors[0].message,
+            "Type '{ a: string; }' is not assignable to type 'Foo'.\nTypes of property 'a' are \
+             incompatible.\nType 'string' is not assignable to type 'number'."
+        );
+    }
+
+    #[test]
+    fn test_dialect_restricts_formats() {
+        let buf = "src/a.ts(1,1): error TS2304: Cannot find name 'x'.\nTS2304 [ERROR]: Cannot find \
+                   name 'y'.\n    at file:///app/main.ts:1:1\n";
+
+        assert_eq!(Parser::new().parse_all(buf).len(), 2);
+        assert_eq!(Parser::with_dialect(Dialect::Tsc).parse_all(buf).len(), 1);
+        assert_eq!(
+            Parser::with_dialect(Dialect::Deno).parse_all(buf)[0].file(),
+            Some("/app/main.ts")
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use super::{
+    ErrorCode,
+    TsError,
+    parse_category,
+};
+
+/// Parse the header line of a diagnostic in tsc's `--pretty` output, the ANSI codes have to be
+/// stripped already: `src/index.ts:2:7 - error TS2322: Type 'string' is not assignable ...`
+pub fn parse(line: &str) -> Option<TsError> {
+    // The path itself may contain " - ", so every separator is tried until one is preceded by a
+    // `:line:col` location and followed by a category and code
+    for (idx, _) in line.match_indices(" - ") {
+        let location = &line[..idx];
+        let Some((rest, col_s)) = location.rsplit_once(':') else {
+            continue;
+        };
+        let Some((file, line_s)) = rest.rsplit_once(':') else {
+            continue;
+        };
+        let (Ok(line_num), Ok(column)) = (line_s.parse(), col_s.parse()) else {
+            continue;
+        };
+        let Some((category, code, msg)) = parse_category(&line[idx + " - ".len()..]) else {
+            continue;
+        };
+
+        if file.is_empty() {
+            return None;
+        }
+
+        return Some(
+            TsError::new(file, line_num, column, ErrorCode::from_str(code), msg)
+                .with_category(category),
+        );
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pretty_header() {
+        let err = parse(
+            "src/index.ts:2:7 - error TS2322: Type 'string' is not assignable to type 'number'.",
+        )
+        .unwrap();
+
+        assert_eq!(err.file(), Some("src/index.ts"));
+        assert_eq!(err.line(), 2);
+        assert_eq!(err.column(), 7);
+        assert_eq!(err.code, ErrorCode::TypeMismatch);
+        assert_eq!(
+            err.message,
+            "Type 'string' is not assignable to type 'number'."
+        );
+    }
+
+    #[test]
+    fn test_parse_pretty_header_with_dash_in_path() {
+        let err = parse("src/a - b/index.ts:10:1 - error TS2304: Cannot find name 'foo'.").unwrap();
+
+        assert_eq!(err.file(), Some("src/a - b/index.ts"));
+        assert_eq!(err.line(), 10);
+    }
+
+    #[test]
+    fn test_parse_pretty_ignores_code_frames() {
+        assert!(parse("2 const x: number = \"hello\";").is_none());
+        assert!(parse("        ~").is_none());
+        assert!(parse("Found 2 errors in 2 files.").is_none());
+        assert!(parse("     1  src/index.ts:2").is_none());
+    }
+}
@@ -0,0 +1,146 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{
+    ErrorCode,
+    Severity,
+    TsError,
+};
+
+/// A line of `svelte-check --output machine-verbose` output we care about
+#[derive(Debug)]
+pub enum Record {
+    /// `START "/path/to/workspace"`, diagnostic filenames are relative to it
+    Start(String),
+    Diagnostic(TsError),
+}
+
+#[derive(Deserialize)]
+struct Diagnostic {
+    #[serde(rename = "type")]
+    kind:     String,
+    filename: String,
+    start:    Option<Position>,
+    message:  String,
+    code:     Option<Value>,
+}
+
+/// 0-based position as reported by svelte-check
+#[derive(Deserialize)]
+struct Position {
+    line:      usize,
+    character: usize,
+}
+
+/// Parse a line of `svelte-check --output machine-verbose` output. Every line is prefixed with a
+/// timestamp: `1590680326807 {"type":"ERROR","filename":"App.svelte",...}`
+pub fn parse(line: &str) -> Option<Record> {
+    let (timestamp, rest) = line.split_once(' ')?;
+    if timestamp.is_empty() || !timestamp.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    if let Some(workspace) = rest.strip_prefix("START ") {
+        let workspace = serde_json::from_str(workspace).ok()?;
+        return Some(Record::Start(workspace));
+    }
+
+    let diagnostic: Diagnostic = serde_json::from_str(rest).ok()?;
+    let severity = match diagnostic.kind.as_str() {
+        "ERROR" => Severity::Error,
+        "WARNING" => Severity::Warning,
+        _ => Severity::Message,
+    };
+    let code = normalize_code(diagnostic.code.as_ref());
+    let start = diagnostic.start.unwrap_or(Position {
+        line:      0,
+        character: 0,
+    });
+
+    Some(Record::Diagnostic(
+        TsError::new(
+            diagnostic.filename,
+            start.line + 1,
+            start.character + 1,
+            code,
+            diagnostic.message,
+        )
+        .with_category(severity),
+    ))
+}
+
+/// TypeScript codes come without the `TS` prefix, either as a number or a string. Codes of the
+/// svelte compiler itself (e.g. `a11y-missing-attribute`) are kept as they are, diagnostics without
+/// a code are reported as `svelte`.
+fn normalize_code(code: Option<&Value>) -> ErrorCode {
+    let code = match code {
+        Some(Value::Number(number)) => number.to_string(),
+        Some(Value::String(code)) if !code.is_empty() => code.clone(),
+        _ => return ErrorCode::Svelte("svelte".to_string()),
+    };
+
+    if code.bytes().all(|b| b.is_ascii_digit()) {
+        ErrorCode::from_str(&format!("TS{}", code))
+    } else {
+        ErrorCode::Svelte(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic(line: &str) -> TsError {
+        match parse(line) {
+            Some(Record::Diagnostic(err)) => err,
+            other => panic!("expected a diagnostic, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_start() {
+        assert!(matches!(
+            parse("1590680326283 START \"/home/dev/app\""),
+            Some(Record::Start(workspace)) if workspace == "/home/dev/app"
+        ));
+        assert!(parse("1590680326807 COMPLETED 20 FILES 1 ERRORS 0 WARNINGS").is_none());
+        assert!(parse("src/a.ts(1,1): error TS2322: nope").is_none());
+    }
+
+    #[test]
+    fn test_parse_typescript_diagnostic() {
+        let err = diagnostic(
+            r#"1590680326807 {"type":"ERROR","filename":"src/App.svelte","start":{"line":4,"character":8},"end":{"line":4,"character":13},"message":"Type 'string' is not assignable to type 'number'.","code":2322,"source":"ts"}"#,
+        );
+
+        assert_eq!(err.file(), Some("src/App.svelte"));
+        assert_eq!(err.line(), 5);
+        assert_eq!(err.column(), 9);
+        assert_eq!(err.code, ErrorCode::TypeMismatch);
+        assert_eq!(err.severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_parse_svelte_diagnostic() {
+        let err = diagnostic(
+            r#"1590680326807 {"type":"WARNING","filename":"src/App.svelte","start":{"line":0,"character":0},"end":{"line":0,"character":10},"message":"A11y: <img> element should have an alt attribute","code":"a11y-missing-attribute","source":"svelte"}"#,
+        );
+
+        assert_eq!(
+            err.code,
+            ErrorCode::Svelte("a11y-missing-attribute".to_string())
+        );
+        assert_eq!(err.code.to_string(), "a11y-missing-attribute");
+        assert_eq!(err.severity, Severity::Warning);
+        assert_eq!(
+            err.message,
+            "A11y: <img> element should have an alt attribute"
+        );
+
+        let uncoded = diagnostic(
+            r#"1590680326807 {"type":"ERROR","filename":"src/App.svelte","message":"Unexpected token"}"#,
+        );
+        assert_eq!(uncoded.code.to_string(), "svelte");
+        assert_eq!(uncoded.message, "Unexpected token");
+    }
+}
@@ -0,0 +1,56 @@
+use super::build::strip_timestamp;
+
+/// A cycle marker of `tsc --watch`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchEvent {
+    /// `Starting compilation in watch mode...` or `File change detected. Starting incremental
+    /// compilation...`
+    Starting,
+    /// `Found 2 errors. Watching for file changes.`
+    Finished,
+}
+
+/// Parse a cycle marker, with or without the leading timestamp
+pub fn parse(line: &str) -> Option<WatchEvent> {
+    let line = strip_timestamp(line.trim_end());
+
+    if line.starts_with("Starting compilation in watch mode")
+        || line.starts_with("File change detected. Starting incremental compilation")
+    {
+        return Some(WatchEvent::Starting);
+    }
+    if line.starts_with("Found ") && line.ends_with("Watching for file changes.") {
+        return Some(WatchEvent::Finished);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_watch_events() {
+        assert_eq!(
+            parse("12:00:00 AM - Starting compilation in watch mode..."),
+            Some(WatchEvent::Starting)
+        );
+        assert_eq!(
+            parse("[12:00:05 AM] File change detected. Starting incremental compilation..."),
+            Some(WatchEvent::Starting)
+        );
+        assert_eq!(
+            parse("12:00:01 AM - Found 1 error. Watching for file changes."),
+            Some(WatchEvent::Finished)
+        );
+        assert_eq!(
+            parse("[12:00:01 AM] Found 0 errors. Watching for file changes.\r"),
+            Some(WatchEvent::Finished)
+        );
+        assert_eq!(parse("Found 2 errors in 2 files."), None);
+        assert_eq!(
+            parse("src/a.ts(1,1): error TS2304: Cannot find name 'Found'."),
+            None
+        );
+    }
+}
@@ -1,5 +1,8 @@
+use std::ops::Range;
+
 use ariadne::{
     Color,
+    Config,
     Label,
     Report,
     ReportKind,
@@ -10,90 +13,190 @@ use colored::*;
 use crate::{
     error::{
         ErrorDiagnostic,
+        Severity,
         TsError,
     },
-    tokenizer::Tokenizer,
+    sfc,
+    suggestion::Suggestion,
+    tokenizer::Token,
 };
 
-/// Pretty format
-pub fn fmt(err: &TsError) -> String {
-    let src = std::fs::read_to_string(&err.file).unwrap_or_default();
-    if src.is_empty() {
-        return fmt_simple(err);
+/// Source, tokens, span and suggestion resolved for a single `TsError`
+#[derive(Debug, Clone)]
+pub struct Analysis {
+    pub src:        String,
+    /// Span of the error itself, `None` when the source could not be read
+    pub span:       Option<Range<usize>>,
+    pub suggestion: Option<Suggestion>,
+}
+
+impl Analysis {
+    /// The span the report points at, the suggestion's span takes precedence over the error span
+    pub fn label_span(&self) -> Option<Range<usize>> {
+        self.suggestion
+            .as_ref()
+            .and_then(|s| s.span.clone())
+            .or_else(|| self.span.clone())
+    }
+}
+
+/// Read the source of the error and resolve the span and suggestion for it
+pub fn analyze(err: &TsError) -> Analysis {
+    let src = err
+        .file()
+        .and_then(|file| std::fs::read_to_string(file).ok())
+        .unwrap_or_default();
+    let tokens = sfc::tokenize(err.file().unwrap_or_default(), &src);
+    let span = if src.is_empty() {
+        None
+    } else {
+        Some(locate(err, &src, &tokens))
+    };
+    let suggestion = err.code.suggest(err, &tokens);
+
+    Analysis {
+        src,
+        span,
+        suggestion,
     }
+}
+
+/// Find the span of the error, from the reported end position, the token at the error position or
+/// from line/column
+fn locate(err: &TsError, src: &str, tokens: &[Token]) -> Range<usize> {
+    let line = err.line();
+    let column = err.column().saturating_sub(1);
 
-    let tokens = Tokenizer::new(src.clone()).tokenize();
-    let mut span = None;
+    if let Some((end_line, end_column)) = err.end
+        && let Some(start) = offset_at(src, line, err.column())
+        && let Some(end) = offset_at(src, end_line, end_column)
+        && start < end
+    {
+        return start..end;
+    }
 
-    for token in &tokens {
-        if token.line == err.line
-            && (err.column - 1) >= token.column
-            && (err.column - 1) < token.column + token.raw.chars().count()
+    for token in tokens {
+        if token.line == line
+            && column >= token.column
+            && column < token.column + token.raw.chars().count()
         {
-            span = Some(token.start..token.end);
-            break;
+            return token.start..token.end;
         }
     }
 
     // If no token matched, calculate span from line/column
-    let span = span.unwrap_or_else(|| {
-        let mut byte_offset = 0;
-        let mut current_line = 1;
-        let mut current_column = 0;
-
-        for ch in src.chars() {
-            if current_line == err.line && current_column == err.column - 1 {
-                // Found the position, use a small span for the character
-                let char_len = ch.len_utf8();
-                return byte_offset..byte_offset + char_len;
-            }
+    let mut byte_offset = 0;
+    let mut current_line = 1;
+    let mut current_column = 0;
 
-            if ch == '\n' {
-                current_line += 1;
-                current_column = 0;
-            } else {
-                current_column += 1;
-            }
+    for ch in src.chars() {
+        if current_line == line && current_column == column {
+            // Found the position, use a small span for the character
+            let char_len = ch.len_utf8();
+            return byte_offset..byte_offset + char_len;
+        }
 
-            byte_offset += ch.len_utf8();
+        if ch == '\n' {
+            current_line += 1;
+            current_column = 0;
+        } else {
+            current_column += 1;
         }
 
-        byte_offset.max(1) - 1..byte_offset
-    });
+        byte_offset += ch.len_utf8();
+    }
 
-    let suggestion = err.code.suggest(err, &tokens);
+    byte_offset.max(1) - 1..byte_offset
+}
+
+/// Convert a 1-indexed line and column (in characters) into a byte offset, the position right
+/// after the last character of a line is valid as well
+pub fn offset_at(src: &str, line: usize, column: usize) -> Option<usize> {
+    let line_start = if line == 1 {
+        0
+    } else {
+        src.match_indices('\n').nth(line.checked_sub(2)?)?.0 + 1
+    };
+    let text = src[line_start..].split('\n').next().unwrap_or_default();
+
+    let column = column.checked_sub(1)?;
+    if column == text.chars().count() {
+        return Some(line_start + text.len());
+    }
+    text.char_indices()
+        .nth(column)
+        .map(|(offset, _)| line_start + offset)
+}
+
+/// Convert a byte offset into a 1-indexed line and column (in characters)
+pub fn position_at(src: &str, offset: usize) -> (usize, usize) {
+    let mut line = 1;
+    let mut column = 1;
+
+    for (i, ch) in src.char_indices() {
+        if i >= offset {
+            break;
+        }
+        if ch == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+    }
+
+    (line, column)
+}
+
+/// The ariadne report kind matching the severity of the diagnostic
+fn report_kind(severity: Severity) -> ReportKind<'static> {
+    match severity {
+        Severity::Error => ReportKind::Error,
+        Severity::Warning => ReportKind::Warning,
+        Severity::Message => ReportKind::Advice,
+    }
+}
+
+/// Pretty format
+pub fn fmt(err: &TsError) -> String {
+    let Some(file) = err.file() else {
+        return fmt_global(err);
+    };
+    let analysis = analyze(err);
+    let Some(span) = analysis.span.clone() else {
+        return fmt_simple(err);
+    };
 
     let mut buf = Vec::new();
 
     // determine the span, either from tokens or the default
-    let label_span = suggestion
-        .as_ref()
-        .and_then(|s| s.span.clone())
-        .unwrap_or_else(|| span.clone());
+    let label_span = analysis.label_span().unwrap_or_else(|| span.clone());
+    let suggestion = analysis.suggestion;
 
-    let mut report = Report::build(ReportKind::Error, (&err.file, span.clone()))
-        .with_code(err.code)
+    let mut report = Report::build(report_kind(err.severity), (file, span.clone()))
+        .with_config(Config::default().with_color(control::SHOULD_COLORIZE.should_colorize()))
+        .with_code(&err.code)
         .with_message(&err.message);
 
     if let Some(ref s) = suggestion {
         if !s.suggestions.is_empty() {
             for suggestion_text in s.suggestions.iter() {
                 report = report.with_label(
-                    Label::new((&err.file, label_span.clone()))
+                    Label::new((file, label_span.clone()))
                         .with_color(Color::Red)
                         .with_message(suggestion_text),
                 );
             }
         } else {
             report = report.with_label(
-                Label::new((&err.file, label_span.clone()))
+                Label::new((file, label_span.clone()))
                     .with_color(Color::Red)
                     .with_message("Error found here ".to_string()),
             );
         }
     } else {
         report = report.with_label(
-            Label::new((&err.file, label_span))
+            Label::new((file, label_span))
                 .with_color(Color::Red)
                 .with_message("Error found here ".to_string()),
         );
@@ -107,24 +210,83 @@ pub fn fmt(err: &TsError) -> String {
                 .unwrap_or_else(|| "No suggestion available.".to_string()),
         )
         .finish()
-        .write((&err.file, Source::from(src)), &mut buf)
+        .write((file, Source::from(analysis.src)), &mut buf)
         .ok();
 
     String::from_utf8(buf).unwrap_or_else(|_| fmt_simple(err))
 }
 
+/// Colored severity label, e.g. `error`
+fn severity_label(severity: Severity) -> ColoredString {
+    match severity {
+        Severity::Error => "error".red().bold(),
+        Severity::Warning => "warning".yellow().bold(),
+        Severity::Message => "message".blue().bold(),
+    }
+}
+
 /// Simple formatting without src extraction
 fn fmt_simple(err: &TsError) -> String {
+    let file = err.file().unwrap_or_default();
     format!(
         "{}:{}:{} - {} {}: {}\n  --> {}:{}:{}\n      |\n      = TypeScript compiler error\n",
-        err.file.cyan(),
-        err.line.to_string().yellow(),
-        err.column.to_string().yellow(),
-        "error".red().bold(),
+        file.cyan(),
+        err.line().to_string().yellow(),
+        err.column().to_string().yellow(),
+        severity_label(err.severity),
+        err.code.to_string().red().bold(),
+        err.message,
+        file.cyan(),
+        err.line().to_string().cyan(),
+        err.column().to_string().cyan()
+    )
+}
+
+/// Global diagnostics have no source to point at, usually the tsconfig or the command line is
+/// at fault
+fn fmt_global(err: &TsError) -> String {
+    format!(
+        "{} {} {}: {}\n      |\n      = Not tied to a file, check tsconfig.json and the tsc \
+         arguments\n",
+        "global".magenta().bold(),
+        severity_label(err.severity),
         err.code.to_string().red().bold(),
         err.message,
-        err.file.cyan(),
-        err.line.to_string().cyan(),
-        err.column.to_string().cyan()
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::codes::ErrorCode;
+
+    #[test]
+    fn test_position_at() {
+        let src = "let a = 1;\nlet b: number = \"x\";\n";
+        assert_eq!(position_at(src, 0), (1, 1));
+        assert_eq!(position_at(src, 4), (1, 5));
+        assert_eq!(position_at(src, 15), (2, 5));
+    }
+
+    #[test]
+    fn test_offset_at() {
+        let src = "let a = 1;\nlet b: number = \"x\";\n";
+        assert_eq!(offset_at(src, 1, 1), Some(0));
+        assert_eq!(offset_at(src, 2, 5), Some(15));
+        assert_eq!(offset_at(src, 1, 11), Some(10));
+        assert_eq!(offset_at(src, 1, 12), None);
+        assert_eq!(offset_at(src, 4, 1), None);
+    }
+
+    #[test]
+    fn test_fmt_global() {
+        let err = TsError::global(
+            ErrorCode::Unsupported(18003),
+            "No inputs were found in config file 'tsconfig.json'.",
+        );
+
+        let out = crate::ansi::strip(&fmt(&err));
+
+        assert!(out.starts_with("global error TS18003: No inputs were found"));
+    }
+}
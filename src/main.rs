@@ -1,23 +1,119 @@
-use anyhow::Result;
+use std::{
+    collections::HashMap,
+    io::{
+        BufRead,
+        BufReader,
+        IsTerminal,
+        Read,
+    },
+    process::{
+        Child,
+        ChildStdout,
+        Stdio,
+    },
+    sync::{
+        Arc,
+        Mutex,
+        PoisonError,
+    },
+    thread::JoinHandle,
+};
+
+use anyhow::{
+    Context,
+    Result,
+};
 use clap::Parser;
 use colored::*;
 
-use crate::error::codes::ErrorCode;
+use crate::{
+    checker::Checker,
+    error::{
+        Severity,
+        TsError,
+        codes::ErrorCode,
+    },
+    report::{
+        Format,
+        Invocation,
+        Report,
+    },
+};
 
+mod ansi;
+mod checker;
 mod diagnostics;
 mod error;
 mod formatter;
 mod message_parser;
+mod report;
+mod sfc;
 mod suggestion;
 mod token_utils;
 mod tokenizer;
+mod tsconfig;
+mod watch;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
-    /// Optional file to read TSC error output from. If not provided, runs `tsc` in the current
-    /// directory.
-    input: Option<String>,
+    /// Optional files to type-check with the options of their tsconfig.json. If not provided, runs
+    /// `tsc` in the current directory. Use `-` to read already captured tsc output from stdin
+    /// instead.
+    input: Vec<String>,
+
+    /// Read already captured tsc output from this file instead of running tsc
+    #[arg(long, value_name = "PATH", conflicts_with = "input")]
+    tsc_log: Option<String>,
+
+    /// Run `tsc -b --verbose` over a solution tsconfig with project references, defaults to the
+    /// current directory
+    #[arg(
+        long,
+        value_name = "TSCONFIG",
+        num_args = 0..=1,
+        default_missing_value = ".",
+        conflicts_with = "input"
+    )]
+    build: Option<String>,
+
+    /// tsconfig of the project to check, e.g. `tsconfig.test.json`
+    #[arg(
+        short,
+        long,
+        value_name = "TSCONFIG",
+        conflicts_with_all = ["input", "build", "tsc_log"]
+    )]
+    project: Option<String>,
+
+    /// Type checker to run: tsc, tsgo, vue-tsc, deno or custom:<cmd>
+    #[arg(long, value_name = "CHECKER", default_value = "tsc")]
+    checker: Checker,
+
+    /// Also report the results of `eslint --format json` stored in this file, `-` reads stdin
+    #[arg(long, value_name = "PATH")]
+    eslint: Option<String>,
+
+    /// Path of the compiler to run instead of the one found in node_modules/.bin or on PATH
+    #[arg(long, value_name = "PATH")]
+    tsc_path: Option<String>,
+
+    /// Output format of the report
+    #[arg(long, value_enum, default_value_t = Format::Pretty)]
+    format: Format,
+
+    /// Also write a GitLab Code Quality report to this path
+    #[arg(long, value_name = "PATH")]
+    gitlab_report: Option<String>,
+
+    /// Run `tsc --watch` and print the report again after every compilation, only with the pretty
+    /// format
+    #[arg(short, long, conflicts_with = "tsc_log")]
+    watch: bool,
+
+    /// Extra compiler arguments after `--`, e.g. `-- --noUncheckedIndexedAccess`
+    #[arg(last = true, value_name = "COMPILER_ARGS", conflicts_with = "tsc_log")]
+    compiler_args: Vec<String>,
 
     /// Format a diagnostic from LSP instead of running tsc
     #[arg(long)]
@@ -55,113 +151,519 @@ fn main() -> Result<()> {
             cli.column.expect("--column required"),
             cli.code.expect("--code required"),
             cli.message.expect("--message required"),
+            cli.format,
         )?;
     } else {
         // Default behavior: parse tsc output
-        parse_tsc_output(cli.input)?;
+        parse_tsc_output(&cli)?;
     }
 
     Ok(())
 }
 
 fn format_lsp_diagnostic(
-    file: String, line: usize, column: usize, code: String, message: String,
+    file: String, line: usize, column: usize, code: String, message: String, format: Format,
 ) -> Result<()> {
-    let parsed = error::TsError {
-        file,
-        line,
-        column,
-        code: ErrorCode::from_str(&code),
-        message,
-    };
+    let parsed = error::TsError::new(file, line, column, ErrorCode::from_str(&code), message);
 
-    println!("{}", formatter::fmt(&parsed));
+    if format == Format::Pretty {
+        println!("{}", formatter::fmt(&parsed));
+    } else if format == Format::Lsp {
+        let entry = report::Entry::new(parsed);
+        println!(
+            "{}",
+            serde_json::to_string(&report::lsp::diagnostic(&entry))?
+        );
+    } else {
+        let mut report = Report::default();
+        report.push(parsed);
+        println!("{}", report.render(format)?);
+    }
     Ok(())
 }
 
-fn parse_tsc_output(input: Option<String>) -> Result<()> {
-    let buf: String;
-
-    if let Some(input_file) = input {
-        // Execute tsc on a specific file
-        // Note: When tsc is run with a file argument, it doesn't use tsconfig.json
-        // So we need to pass compiler options explicitly
-        let output = std::process::Command::new("tsc")
-            .arg(&input_file)
-            .args([
-                "--pretty",
-                "false",
-                "--diagnostics",
-                "--extendedDiagnostics",
-                "--noEmit",
-                "--preserveWatchOutput",
-                "false",
-                "--noUnusedLocals",
-                "--noUnusedParameters",
-                "--strict",
-            ])
-            .output()?;
-        buf = format!(
-            "{}{}",
-            String::from_utf8_lossy(&output.stdout),
-            String::from_utf8_lossy(&output.stderr)
-        );
+/// Where the checker output comes from
+enum Output {
+    /// Captured by an earlier run
+    Log(String),
+    /// The checker is still running
+    Running(Running),
+}
+
+fn parse_tsc_output(cli: &Cli) -> Result<()> {
+    let output = if let Some(log) = &cli.tsc_log {
+        Output::Log(read_tsc_log(log)?)
+    } else if cli.input == ["-"] {
+        Output::Log(read_tsc_log("-")?)
+    } else if cli.watch {
+        return watch_tsc(cli);
+    } else {
+        Output::Running(run_tsc(cli)?)
+    };
+
+    report_output(cli, output)
+}
+
+/// Read tsc output captured by an earlier run, `-` reads from stdin
+fn read_tsc_log(path: &str) -> Result<String> {
+    if path == "-" {
+        let mut buf = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut buf)?;
+        return Ok(buf);
+    }
+
+    let bytes =
+        std::fs::read(path).with_context(|| format!("Could not read tsc log `{}`", path))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// The program and arguments to run the checker with. The temporary tsconfig for checking single
+/// files is removed when it is dropped, so it has to outlive the checker.
+fn checker_command(cli: &Cli) -> Result<(String, Vec<String>, Option<tsconfig::FileConfig>)> {
+    let checker = &cli.checker;
+    let (program, mut args) = checker.command();
+    let mut file_config = None;
+
+    if let Some(project) = &cli.build {
+        let Some(build_args) = checker.build_args(project) else {
+            anyhow::bail!("--build is only supported with tsc, tsgo and vue-tsc");
+        };
+        args.extend(build_args);
+    } else if let Some(project) = &cli.project {
+        let Some(project_args) = checker.project_args(project) else {
+            anyhow::bail!("--project is only supported with tsc, tsgo and vue-tsc");
+        };
+        args.extend(project_args);
+    } else if !cli.input.is_empty() {
+        if checker.uses_tsconfig() {
+            file_config = tsconfig::FileConfig::create(&cli.input)?;
+        }
+        match file_config
+            .as_ref()
+            .and_then(|c| checker.project_args(&c.path()))
+        {
+            Some(project_args) => args.extend(project_args),
+            // Not part of a project, the files are checked on their own
+            None => args.extend(checker.file_args(&cli.input)),
+        }
+    } else if *checker == Checker::Deno {
+        anyhow::bail!("deno check needs an entry point, e.g. `ts-analyzer --checker deno main.ts`");
     } else {
-        // Execute tsc in the current directory
-        let output = std::process::Command::new("tsc")
-            .args([
-                "--pretty",
-                "false",
-                "--diagnostics",
-                "--extendedDiagnostics",
-                "--noEmit",
-                "--preserveWatchOutput",
-                "false",
-            ])
-            .output()?;
-        buf = format!(
-            "{}{}",
-            String::from_utf8_lossy(&output.stdout),
-            String::from_utf8_lossy(&output.stderr)
+        args.extend(checker.default_args());
+    }
+
+    if cli.watch {
+        if !checker.uses_tsconfig() {
+            anyhow::bail!("--watch is only supported with tsc, tsgo and vue-tsc");
+        }
+        // tsc clears the screen before every compilation otherwise
+        let watch_args = ["--watch", "--preserveWatchOutput"].map(String::from);
+        args = checker::merge_args(args, &watch_args).0;
+    } else if lists_files(cli) {
+        args.push("--listFiles".to_string());
+    }
+
+    let (args, ignored) = checker::merge_args(args, &cli.compiler_args);
+    for arg in ignored {
+        eprintln!(
+            "{} ignoring `{}`, ts-analyzer needs `--pretty false` and `--noEmit` to read the output",
+            "warning:".yellow().bold(),
+            arg
         );
     }
 
-    if buf.is_empty() {
-        println!("No output from tsc.");
-        return Ok(());
+    let program = checker.locate(&program, cli.tsc_path.as_deref())?;
+    Ok((program, args, file_config))
+}
+
+/// Files without errors are only reported as passing when tsc tells us what it checked. Its
+/// `--listFiles` output is only recognised when ts-analyzer asked for it.
+fn lists_files(cli: &Cli) -> bool {
+    cli.format == Format::Junit && cli.build.is_none() && !cli.watch && cli.checker.lists_files()
+}
+
+/// A checker that is still running. Its stdout is read line by line while it runs, stderr is
+/// collected on a thread so the checker never blocks on a full pipe.
+struct Running {
+    child:        Arc<Mutex<Child>>,
+    stdout:       BufReader<ChildStdout>,
+    stderr:       JoinHandle<String>,
+    program:      String,
+    args:         Vec<String>,
+    /// Removed once the checker is done
+    _file_config: Option<tsconfig::FileConfig>,
+}
+
+impl Running {
+    /// The next line of stdout without its line ending, `None` once the checker closed it
+    fn next_line(&mut self, line: &mut Vec<u8>) -> Result<Option<String>> {
+        line.clear();
+        if self.stdout.read_until(b'\n', line)? == 0 {
+            return Ok(None);
+        }
+        let text = String::from_utf8_lossy(line);
+        Ok(Some(text.trim_end_matches(['\n', '\r']).to_string()))
+    }
+
+    /// Wait for the checker to exit, returns what it wrote to stderr
+    fn wait(self, checker: &Checker) -> Result<(String, Invocation)> {
+        let status = self
+            .child
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .wait()?;
+        let stderr = self.stderr.join().unwrap_or_default();
+
+        Ok((
+            stderr,
+            Invocation {
+                version:   checker::version(checker, &self.program),
+                program:   self.program,
+                args:      self.args,
+                exit_code: status.code(),
+            },
+        ))
     }
+}
+
+/// Start the checker
+fn run_tsc(cli: &Cli) -> Result<Running> {
+    let (program, args, file_config) = checker_command(cli)?;
+
+    let mut child = std::process::Command::new(&program)
+        .args(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Could not run `{}`", program))?;
 
-    let mut found_error = false;
-    let mut counter: usize = 0;
-    let lines: Vec<&str> = buf.lines().collect();
-    let mut i = 0;
+    let stdout = child
+        .stdout
+        .take()
+        .context("stdout of the checker is not piped")?;
+    let mut stderr = child
+        .stderr
+        .take()
+        .context("stderr of the checker is not piped")?;
+    let stderr = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stderr.read_to_end(&mut buf);
+        String::from_utf8_lossy(&buf).into_owned()
+    });
 
-    while i < lines.len() {
-        if let Some(mut parsed) = error::parse(lines[i]) {
-            found_error = true;
-            counter += 1;
+    let child = Arc::new(Mutex::new(child));
+    tsconfig::remove_on_signal(file_config.as_ref(), Arc::clone(&child))?;
 
-            // Collect continuation lines (indented lines following the error)
-            let mut indented_line = i + 1;
-            while indented_line < lines.len() && lines[indented_line].starts_with("  ") {
-                parsed.message.push('\n');
-                parsed.message.push_str(lines[indented_line].trim());
-                indented_line += 1;
+    Ok(Running {
+        child,
+        stdout: BufReader::new(stdout),
+        stderr,
+        program,
+        args,
+        _file_config: file_config,
+    })
+}
+
+/// Run the checker in watch mode and print the report after every compilation, together with the
+/// diagnostics that are new and the ones that were fixed since the previous one
+fn watch_tsc(cli: &Cli) -> Result<()> {
+    // New and fixed diagnostics have no place in the machine readable formats
+    if cli.format != Format::Pretty {
+        let format = clap::ValueEnum::to_possible_value(&cli.format)
+            .map(|value| value.get_name().to_string())
+            .unwrap_or_default();
+        anyhow::bail!(
+            "--watch only supports the pretty format, not `--format {}`",
+            format
+        );
+    }
+
+    let (program, args, file_config) = checker_command(cli)?;
+    let checked = checked_files(cli)?;
+    let mut previous: Option<Vec<TsError>> = None;
+
+    let parser = cli.checker.parser();
+    watch::run(&program, &args, parser, file_config, |mut diagnostics| {
+        diagnostics.retain(|err| {
+            err.file()
+                .is_none_or(|file| checked.as_ref().is_none_or(|c| c.contains(file)))
+        });
+
+        // Nothing is new in the first compilation
+        let (new, fixed) = match &previous {
+            Some(previous) => watch::diff(previous, &diagnostics),
+            None => (vec![false; diagnostics.len()], Vec::new()),
+        };
+        print_cycle(cli, &diagnostics, &new, &fixed)?;
+
+        previous = Some(diagnostics);
+        Ok(())
+    })
+}
+
+fn print_cycle(cli: &Cli, diagnostics: &[TsError], new: &[bool], fixed: &[&TsError]) -> Result<()> {
+    if let Some(path) = &cli.gitlab_report {
+        let mut report = Report::default();
+        for err in diagnostics {
+            report.push(err.clone());
+        }
+        std::fs::write(path, report::gitlab::render(&report)?)?;
+    }
+
+    if std::io::stdout().is_terminal() {
+        // Clear the screen, the report replaces the one of the previous compilation
+        print!("\x1b[2J\x1b[H");
+    }
+
+    for (err, new) in diagnostics.iter().zip(new) {
+        if *new {
+            println!("{}", "new".green().bold());
+        }
+        println!("{}", formatter::fmt(err));
+    }
+    if diagnostics.is_empty() {
+        println!("No errors were emitted.");
+    }
+
+    let mut totals = Totals::default();
+    for err in diagnostics {
+        totals.add(err);
+    }
+    totals.print();
+
+    let new = new.iter().filter(|new| **new).count();
+    if new > 0 || !fixed.is_empty() {
+        println!(
+            "New: {}, fixed: {}",
+            new.to_string().red().bold(),
+            fixed.len().to_string().green().bold()
+        );
+    }
+    for err in fixed {
+        let location = err
+            .location
+            .as_ref()
+            .map(|l| format!("{}:{}:{} ", l.file, l.line, l.column))
+            .unwrap_or_default();
+        let message = err.message.lines().next().unwrap_or_default();
+        println!(
+            "  {} {}{}: {}",
+            "fixed".green(),
+            location,
+            err.code,
+            message
+        );
+    }
+
+    println!("\nWatching for file changes...");
+    Ok(())
+}
+
+/// The files to report diagnostics for when files were given, tsc reports the files they import
+/// as well
+fn checked_files(cli: &Cli) -> Result<Option<tsconfig::CheckedFiles>> {
+    if cli.input.is_empty() || !cli.checker.uses_tsconfig() {
+        return Ok(None);
+    }
+    tsconfig::CheckedFiles::new(&cli.input).map(Some)
+}
+
+/// Number of diagnostics per severity, warnings and messages don't count as errors
+#[derive(Default)]
+struct Totals {
+    errors:   usize,
+    warnings: usize,
+    messages: usize,
+}
+
+impl Totals {
+    fn add(&mut self, err: &TsError) {
+        match err.severity {
+            Severity::Error => self.errors += 1,
+            Severity::Warning => self.warnings += 1,
+            Severity::Message => self.messages += 1,
+        }
+    }
+
+    /// `Total errors: 2, warnings: 1`, warnings and messages are left out when there are none
+    fn print(&self) {
+        let mut line = format!("\nTotal errors: {}", self.errors.to_string().red().bold());
+        if self.warnings > 0 {
+            line.push_str(&format!(
+                ", warnings: {}",
+                self.warnings.to_string().yellow().bold()
+            ));
+        }
+        if self.messages > 0 {
+            line.push_str(&format!(", messages: {}", self.messages.to_string().bold()));
+        }
+        println!("{}", line);
+    }
+}
+
+/// Diagnostics are printed as soon as they are parsed, they are only kept when a report is rendered
+/// after the run
+struct Summary {
+    format:      Format,
+    collect:     bool,
+    report:      Report,
+    counter:     usize,
+    totals:      Totals,
+    per_project: HashMap<String, usize>,
+}
+
+impl Summary {
+    fn push(&mut self, err: TsError) {
+        self.counter += 1;
+        self.totals.add(&err);
+        if let Some(project) = &err.project
+            && err.severity == Severity::Error
+        {
+            *self.per_project.entry(project.clone()).or_default() += 1;
+        }
+
+        if self.format == Format::Pretty {
+            println!("{}", formatter::fmt(&err));
+        }
+        if self.collect {
+            self.report.push(err);
+        }
+    }
+
+    /// Record a line of `--listFiles` output
+    fn list_file(&mut self, line: &str, checked: Option<&tsconfig::CheckedFiles>) {
+        if self.collect
+            && let Some(file) = error::parse_listed_file(line)
+            && !file.contains("node_modules")
+            && checked.is_none_or(|c| c.contains(file))
+        {
+            self.report.files.push(error::relative_to_cwd(file));
+        }
+    }
+}
+
+/// Parse tsc output and print the report
+fn report_output(cli: &Cli, output: Output) -> Result<()> {
+    let format = cli.format;
+    let mut summary = Summary {
+        format,
+        // Every diagnostic has to be kept around when a report is rendered after the run
+        collect: format != Format::Pretty || cli.gitlab_report.is_some(),
+        report: Report::default(),
+        counter: 0,
+        totals: Totals::default(),
+        per_project: HashMap::new(),
+    };
+    let mut has_output = false;
+
+    let parser = match output {
+        Output::Log(buf) => {
+            has_output = !buf.is_empty();
+            // Captured output may come from any checker, the format is detected per diagnostic
+            let mut parser = error::parser::Parser::new();
+            // ESLint results can be piped in on their own as well
+            let diagnostics = error::eslint::parse(&buf).unwrap_or_else(|| parser.parse_all(&buf));
+            for err in diagnostics {
+                summary.push(err);
             }
+            parser
+        }
+        Output::Running(mut running) => {
+            let mut parser = cli.checker.parser();
+            // Only the checked files are reported, not the files they import
+            let checked = checked_files(cli)?;
+            let list_files = lists_files(cli);
+            let report_checked = |summary: &mut Summary, err: TsError| {
+                if err
+                    .file()
+                    .is_none_or(|file| checked.as_ref().is_none_or(|c| c.contains(file)))
+                {
+                    summary.push(err);
+                }
+            };
+
+            let mut buf = Vec::new();
+            while let Some(line) = running.next_line(&mut buf)? {
+                has_output = true;
+                if list_files {
+                    summary.list_file(&line, checked.as_ref());
+                }
+                if let Some(err) = parser.push(&line) {
+                    report_checked(&mut summary, err);
+                }
+            }
+            if let Some(err) = parser.finish() {
+                report_checked(&mut summary, err);
+            }
+
+            // deno writes its diagnostics to stderr, they are reported once it exits
+            let (stderr, invocation) = running.wait(&cli.checker)?;
+            has_output |= !stderr.is_empty();
+            for err in parser.parse_all(&stderr) {
+                report_checked(&mut summary, err);
+            }
+            summary.report.invocation = Some(invocation);
+            parser
+        }
+    };
 
-            println!("{}", formatter::fmt(&parsed));
-            i = indented_line;
-        } else {
-            i += 1;
+    if !has_output && format == Format::Pretty && cli.eslint.is_none() {
+        println!("No output from tsc.");
+        return Ok(());
+    }
+
+    if let Some(path) = &cli.eslint {
+        let lint = read_tsc_log(path)?;
+        let diagnostics = error::eslint::parse(&lint)
+            .with_context(|| format!("`{}` is not `eslint --format json` output", path))?;
+        for err in diagnostics {
+            summary.push(err);
         }
     }
-    if !found_error {
+
+    let Summary {
+        report,
+        counter,
+        totals,
+        per_project,
+        ..
+    } = summary;
+
+    if let Some(path) = &cli.gitlab_report {
+        std::fs::write(path, report::gitlab::render(&report)?)?;
+    }
+
+    if format != Format::Pretty {
+        println!("{}", report.render(format)?);
+        return Ok(());
+    }
+
+    if counter == 0 {
         println!("No errors were emitted.");
     }
 
-    let counter_str = counter.to_string();
+    totals.print();
+
+    if let Some(invocation) = &report.invocation {
+        println!(
+            "Checked with: {} {}",
+            invocation.program.cyan(),
+            invocation.version.as_deref().unwrap_or("(unknown version)")
+        );
+    }
 
-    println!("\nTotal errors: {}", counter_str.red().bold());
+    if !parser.projects().is_empty() {
+        println!("\nErrors per project:");
+        for project in parser.projects() {
+            let count = per_project.get(project).copied().unwrap_or_default();
+            let count = if count > 0 {
+                count.to_string().red().bold()
+            } else {
+                count.to_string().green()
+            };
+            println!("  {}: {}", project.cyan(), count);
+        }
+    }
 
     Ok(())
 }
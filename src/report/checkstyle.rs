@@ -0,0 +1,121 @@
+use super::{
+    Entry,
+    Report,
+    escape_xml_attr,
+};
+use crate::error::Severity;
+
+/// Render the report as Checkstyle XML, diagnostics are grouped per file. Checkstyle has no place
+/// for global diagnostics, they are reported on line 1 of the tsconfig.
+pub fn render(report: &Report) -> String {
+    let mut files: Vec<(String, Vec<&Entry>)> = report
+        .by_file()
+        .into_iter()
+        .map(|(file, entries)| (file.to_string(), entries))
+        .collect();
+    for entry in report.global() {
+        let config = report.config_file(entry);
+        match files.iter_mut().find(|(file, _)| *file == config) {
+            Some((_, entries)) => entries.push(entry),
+            None => files.push((config, vec![entry])),
+        }
+    }
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<checkstyle version=\"4.3\">\n");
+
+    for (file, entries) in &files {
+        xml.push_str(&format!("  <file name=\"{}\">\n", escape_xml_attr(file)));
+        for entry in entries {
+            let err = &entry.error;
+            let severity = match err.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+                Severity::Message => "info",
+            };
+            xml.push_str(&format!(
+                "    <error line=\"{}\" column=\"{}\" severity=\"{}\" message=\"{}\" source=\"{}\"/>\n",
+                err.line().max(1),
+                err.column().max(1),
+                severity,
+                escape_xml_attr(&message(entry)),
+                escape_xml_attr(&err.code.to_string())
+            ));
+        }
+        xml.push_str("  </file>\n");
+    }
+
+    xml.push_str("</checkstyle>\n");
+    xml
+}
+
+/// The tsc message followed by the ts-analyzer suggestions and help
+fn message(entry: &Entry) -> String {
+    let mut message = entry.error.message.clone();
+    if let Some(suggestion) = &entry.suggestion {
+        let suggestions = suggestion.plain_suggestions();
+        if !suggestions.is_empty() {
+            message.push_str("\nSuggestion: ");
+            message.push_str(&suggestions.join(" "));
+        }
+        if let Some(help) = suggestion.plain_help() {
+            message.push_str("\nHelp: ");
+            message.push_str(&help);
+        }
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{
+        TsError,
+        codes::ErrorCode,
+    };
+
+    #[test]
+    fn test_render_checkstyle() {
+        let mut report = Report::default();
+        report.push(TsError::new(
+            "src/index.ts",
+            1,
+            7,
+            ErrorCode::DeclaredButNeverUsed,
+            "'foo' is declared but its value is never read.",
+        ));
+        report.push(TsError::new(
+            "src/index.ts",
+            4,
+            1,
+            ErrorCode::Unsupported(2769),
+            "No overload matches this call.",
+        ));
+
+        let xml = render(&report);
+
+        assert_eq!(xml.matches("<file name=\"src/index.ts\">").count(), 1);
+        assert!(xml.contains(
+            "<error line=\"1\" column=\"7\" severity=\"warning\" message=\"&apos;foo&apos; is declared but its value is never read.&#10;Suggestion: `foo` is unused&#10;Help: Consider removing the reference to `foo`\" source=\"TS6133\"/>"
+        ));
+        assert!(xml.contains(
+            "<error line=\"4\" column=\"1\" severity=\"error\" message=\"No overload matches this call.\" source=\"TS2769\"/>"
+        ));
+    }
+
+    #[test]
+    fn test_render_global_diagnostics() {
+        let mut report = Report::default();
+        report.push(TsError::global(
+            ErrorCode::Unsupported(18003),
+            "No inputs were found in config file 'tsconfig.json'.",
+        ));
+
+        let xml = render(&report);
+
+        assert!(xml.contains("<file name=\"tsconfig.json\">"));
+        assert!(xml.contains(
+            "<error line=\"1\" column=\"1\" severity=\"error\" message=\"No inputs were found in config file &apos;tsconfig.json&apos;.\" source=\"TS18003\"/>"
+        ));
+    }
+}
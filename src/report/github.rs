@@ -0,0 +1,134 @@
+use super::{
+    Entry,
+    Report,
+};
+use crate::error::Severity;
+
+/// Render the report as GitHub Actions workflow commands, one annotation per diagnostic
+pub fn render(report: &Report) -> String {
+    report
+        .entries
+        .iter()
+        .map(annotation)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn annotation(entry: &Entry) -> String {
+    let err = &entry.error;
+    let command = match err.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Message => "notice",
+    };
+
+    let first_suggestion = entry
+        .suggestion
+        .as_ref()
+        .and_then(|s| s.plain_suggestions().into_iter().next());
+    let title = match first_suggestion {
+        Some(suggestion) => format!("{}: {}", err.code, suggestion),
+        None => err.code.to_string(),
+    };
+
+    let mut body = err.message.clone();
+    if let Some(help) = entry.suggestion.as_ref().and_then(|s| s.plain_help()) {
+        body.push_str("\n\nHelp: ");
+        body.push_str(&help);
+    }
+
+    // Global diagnostics become annotations on the workflow run itself
+    let mut properties = Vec::new();
+    if let Some(location) = &err.location {
+        properties.push(format!("file={}", escape_property(&location.file)));
+        properties.push(format!("line={}", location.line));
+        properties.push(format!("col={}", location.column));
+        if let Some(end) = entry.end {
+            properties.push(format!("endLine={}", end.line));
+            if end.line == location.line {
+                properties.push(format!("endColumn={}", end.column));
+            }
+        }
+    }
+    properties.push(format!("title={}", escape_property(&title)));
+
+    format!(
+        "::{} {}::{}",
+        command,
+        properties.join(","),
+        escape_data(&body)
+    )
+}
+
+/// Escape the message part of a workflow command
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a `key=value` property of a workflow command
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{
+        TsError,
+        codes::ErrorCode,
+    };
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape_data("100%\nsure\r"), "100%25%0Asure%0D");
+        assert_eq!(escape_property("a:b,c"), "a%3Ab%2Cc");
+    }
+
+    #[test]
+    fn test_render_annotations() {
+        let mut report = Report::default();
+        report.push(TsError::new(
+            "src/index.ts",
+            3,
+            5,
+            ErrorCode::TypeMismatch,
+            "Type 'string' is not assignable to type 'number'.",
+        ));
+        report.push(TsError::new(
+            "src/index.ts",
+            1,
+            7,
+            ErrorCode::DeclaredButNeverUsed,
+            "'foo' is declared but its value is never read.",
+        ));
+
+        let out = render(&report);
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("::error file=src/index.ts,line=3,col=5,"));
+        assert!(lines[0].contains(",title=TS2322%3A Try converting"));
+        assert!(lines[0].contains("::Type 'string' is not assignable"));
+        assert!(lines[0].ends_with(
+            "%0A%0AHelp: Ensure that the types are compatible or perform an explicit conversion."
+        ));
+        assert!(lines[1].starts_with("::warning file=src/index.ts,line=1,col=7,"));
+        assert!(lines[1].contains("title=TS6133%3A `foo` is unused::"));
+    }
+
+    #[test]
+    fn test_render_global_annotation() {
+        let mut report = Report::default();
+        report.push(TsError::global(
+            ErrorCode::Unsupported(5023),
+            "Unknown compiler option 'foo'.",
+        ));
+
+        assert_eq!(
+            render(&report),
+            "::error title=TS5023::Unknown compiler option 'foo'."
+        );
+    }
+}
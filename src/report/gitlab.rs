@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use super::{
+    Entry,
+    Report,
+};
+use crate::error::Severity;
+
+#[derive(Serialize)]
+struct Issue {
+    description: String,
+    check_name:  String,
+    fingerprint: String,
+    severity:    &'static str,
+    location:    Location,
+}
+
+#[derive(Serialize)]
+struct Location {
+    path:  String,
+    lines: Lines,
+}
+
+#[derive(Serialize)]
+struct Lines {
+    begin: usize,
+}
+
+/// Render the report as a GitLab Code Quality report
+pub fn render(report: &Report) -> anyhow::Result<String> {
+    let mut sources: HashMap<&str, Vec<String>> = HashMap::new();
+    let mut seen: HashMap<u64, usize> = HashMap::new();
+    let mut issues = Vec::with_capacity(report.entries.len());
+
+    for entry in &report.entries {
+        let err = &entry.error;
+        let (file, source_line) = match err.file() {
+            Some(file) => {
+                let lines = sources.entry(file).or_insert_with(|| {
+                    std::fs::read_to_string(file)
+                        .unwrap_or_default()
+                        .lines()
+                        .map(|line| line.trim().to_string())
+                        .collect()
+                });
+                let source_line = lines
+                    .get(err.line().saturating_sub(1))
+                    .cloned()
+                    .unwrap_or_default();
+                (file.to_string(), source_line)
+            }
+            // Code Quality issues need a path, global diagnostics point at the tsconfig
+            None => (report.config_file(entry), String::new()),
+        };
+
+        // Identical diagnostics on identical lines would collide, so the occurrence is part of the
+        // fingerprint as well
+        let key = fingerprint_key(entry, &file, &source_line);
+        let occurrence = seen.entry(key).or_default();
+        *occurrence += 1;
+
+        issues.push(Issue {
+            description: description(entry),
+            check_name:  err.code.to_string(),
+            fingerprint: format!("{:016x}{:016x}", key, fnv1a(&occurrence.to_le_bytes())),
+            severity:    match err.severity {
+                Severity::Error => "major",
+                Severity::Warning => "minor",
+                Severity::Message => "info",
+            },
+            location:    Location {
+                path:  file.replace('\\', "/"),
+                lines: Lines {
+                    begin: err.line().max(1),
+                },
+            },
+        });
+    }
+
+    Ok(serde_json::to_string_pretty(&issues)?)
+}
+
+fn description(entry: &Entry) -> String {
+    let mut description = entry.error.message.clone();
+    if let Some(suggestion) = &entry.suggestion {
+        for line in suggestion.plain_suggestions() {
+            description.push('\n');
+            description.push_str(&line);
+        }
+    }
+    description
+}
+
+/// Fingerprints have to survive unrelated edits, so they are built from the file, code, message and
+/// the text of the offending line rather than from line numbers
+fn fingerprint_key(entry: &Entry, file: &str, source_line: &str) -> u64 {
+    let err = &entry.error;
+    let key = format!(
+        "{}\0{}\0{}\0{}",
+        file.replace('\\', "/"),
+        err.code,
+        err.message,
+        source_line
+    );
+    fnv1a(key.as_bytes())
+}
+
+/// 64-bit FNV-1a, unlike `DefaultHasher` its output is stable across Rust releases
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{
+        TsError,
+        codes::ErrorCode,
+    };
+
+    fn error(line: usize) -> TsError {
+        TsError::new(
+            "src/index.ts",
+            line,
+            7,
+            ErrorCode::DeclaredButNeverUsed,
+            "'foo' is declared but its value is never read.",
+        )
+    }
+
+    #[test]
+    fn test_fingerprints_are_stable_and_unique() {
+        let mut report = Report::default();
+        report.push(error(1));
+        report.push(error(4));
+
+        let first: serde_json::Value = serde_json::from_str(&render(&report).unwrap()).unwrap();
+        let second: serde_json::Value = serde_json::from_str(&render(&report).unwrap()).unwrap();
+
+        assert_eq!(first, second);
+        assert_ne!(first[0]["fingerprint"], first[1]["fingerprint"]);
+        assert_eq!(first[0]["check_name"], "TS6133");
+        assert_eq!(first[0]["severity"], "minor");
+        assert_eq!(first[1]["location"]["lines"]["begin"], 4);
+    }
+
+    #[test]
+    fn test_global_diagnostics_point_at_tsconfig() {
+        let mut report = Report::default();
+        report.push(TsError::global(
+            ErrorCode::Unsupported(5023),
+            "Unknown compiler option 'strictt'.",
+        ));
+
+        let issues: serde_json::Value = serde_json::from_str(&render(&report).unwrap()).unwrap();
+
+        assert_eq!(issues.as_array().map(Vec::len), Some(1));
+        assert_eq!(issues[0]["check_name"], "TS5023");
+        assert_eq!(issues[0]["severity"], "major");
+        assert_eq!(issues[0]["location"]["path"], "tsconfig.json");
+        assert_eq!(issues[0]["location"]["lines"]["begin"], 1);
+        assert_eq!(issues[0]["fingerprint"].as_str().map(str::len), Some(32));
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_line_number() {
+        let mut moved = Report::default();
+        moved.push(error(10));
+        let mut original = Report::default();
+        original.push(error(1));
+
+        let moved: serde_json::Value = serde_json::from_str(&render(&moved).unwrap()).unwrap();
+        let original: serde_json::Value =
+            serde_json::from_str(&render(&original).unwrap()).unwrap();
+
+        assert_eq!(moved[0]["fingerprint"], original[0]["fingerprint"]);
+    }
+}
@@ -0,0 +1,325 @@
+use std::ops::Range;
+
+use super::{
+    Entry,
+    Report,
+    escape_xml,
+};
+use crate::{
+    sfc,
+    tokenizer::{
+        Token,
+        TokenKind,
+    },
+};
+
+/// Lines of source shown above and below a diagnostic
+const CONTEXT: usize = 2;
+
+const STYLE: &str = r#"
+* { box-sizing: border-box; }
+body { margin: 0; display: flex; font: 14px/1.5 system-ui, sans-serif; color: #cdd6f4; background: #1e1e2e; }
+aside { position: sticky; top: 0; height: 100vh; overflow-y: auto; width: 300px; flex-shrink: 0; padding: 1rem; background: #181825; }
+aside h1 { margin: 0 0 .5rem; font-size: 1.2rem; }
+aside select { width: 100%; margin: .5rem 0 1rem; padding: .25rem; background: #313244; color: inherit; border: 1px solid #45475a; }
+aside ul { list-style: none; margin: 0; padding: 0; }
+aside a { display: flex; justify-content: space-between; gap: .5rem; padding: .2rem .4rem; color: #89b4fa; text-decoration: none; word-break: break-all; }
+aside a:hover { background: #313244; }
+.count { color: #fab387; }
+main { flex-grow: 1; min-width: 0; padding: 1rem 2rem; }
+section h2 { font-size: 1rem; color: #89b4fa; border-bottom: 1px solid #45475a; }
+article { margin: 1rem 0 2rem; }
+article header { font-weight: bold; }
+.code { color: #f38ba8; }
+.warning .code { color: #f9e2af; }
+.location { color: #a6adc8; font-weight: normal; }
+.message { white-space: pre-wrap; }
+pre { overflow-x: auto; padding: .5rem 0; background: #11111b; border-radius: 4px; }
+.line { display: block; padding: 0 1rem 0 0; }
+.line.error { background: #3e2e3e; }
+.gutter { display: inline-block; width: 4em; padding-right: 1em; text-align: right; color: #6c7086; user-select: none; }
+.annotation { display: block; padding-left: 5em; color: #f38ba8; white-space: pre-wrap; }
+mark { color: inherit; background: none; text-decoration: underline wavy #f38ba8; }
+.kw { color: #cba6f7; }
+.lit { color: #a6e3a1; }
+.id { color: #cdd6f4; }
+.help { color: #a6e3a1; }
+"#;
+
+const SCRIPT: &str = r##"
+const filter = document.getElementById("code-filter");
+filter.addEventListener("change", () => {
+  const code = filter.value;
+  for (const article of document.querySelectorAll("article")) {
+    article.hidden = code !== "" && article.dataset.code !== code;
+  }
+  for (const section of document.querySelectorAll("section")) {
+    section.hidden = !section.querySelector("article:not([hidden])");
+    document.querySelector(`a[href="#${section.id}"]`).parentElement.hidden = section.hidden;
+  }
+});
+"##;
+
+/// Render the report as a single self-contained HTML page
+pub fn render(report: &Report) -> String {
+    let files: Vec<(&str, Vec<&Entry>)> = report
+        .by_file()
+        .into_iter()
+        .filter(|(_, entries)| !entries.is_empty())
+        .collect();
+    let global = report.global();
+
+    let mut codes: Vec<(String, usize)> = Vec::new();
+    for entry in &report.entries {
+        let code = entry.error.code.to_string();
+        match codes.iter_mut().find(|(c, _)| *c == code) {
+            Some((_, count)) => *count += 1,
+            None => codes.push((code, 1)),
+        }
+    }
+    codes.sort();
+
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>ts-analyzer report</title>\n",
+    );
+    html.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", STYLE));
+
+    // Sidebar
+    html.push_str("<aside>\n<h1>ts-analyzer</h1>\n");
+    html.push_str(&format!(
+        "<p>{} diagnostics in {} files</p>\n",
+        report.entries.len(),
+        files.len()
+    ));
+    html.push_str("<select id=\"code-filter\">\n<option value=\"\">All codes</option>\n");
+    for (code, count) in &codes {
+        html.push_str(&format!(
+            "<option value=\"{0}\">{0} ({1})</option>\n",
+            escape_xml(code),
+            count
+        ));
+    }
+    html.push_str("</select>\n<ul>\n");
+    if !global.is_empty() {
+        html.push_str(&format!(
+            "<li><a href=\"#global\">Global <span class=\"count\">{}</span></a></li>\n",
+            global.len()
+        ));
+    }
+    for (i, (file, entries)) in files.iter().enumerate() {
+        html.push_str(&format!(
+            "<li><a href=\"#file-{}\">{} <span class=\"count\">{}</span></a></li>\n",
+            i,
+            escape_xml(file),
+            entries.len()
+        ));
+    }
+    html.push_str("</ul>\n</aside>\n<main>\n");
+
+    if files.is_empty() && global.is_empty() {
+        html.push_str("<p>No errors were emitted.</p>\n");
+    }
+
+    if !global.is_empty() {
+        html.push_str("<section id=\"global\">\n<h2>Global</h2>\n");
+        for entry in &global {
+            html.push_str(&diagnostic(entry, "", &[]));
+        }
+        html.push_str("</section>\n");
+    }
+
+    for (i, (file, entries)) in files.iter().enumerate() {
+        let src = std::fs::read_to_string(file).unwrap_or_default();
+        let tokens = sfc::tokenize(file, &src);
+
+        html.push_str(&format!(
+            "<section id=\"file-{}\">\n<h2>{}</h2>\n",
+            i,
+            escape_xml(file)
+        ));
+        for entry in entries {
+            html.push_str(&diagnostic(entry, &src, &tokens));
+        }
+        html.push_str("</section>\n");
+    }
+
+    html.push_str(&format!(
+        "</main>\n<script>{}</script>\n</body>\n</html>\n",
+        SCRIPT
+    ));
+    html
+}
+
+fn diagnostic(entry: &Entry, src: &str, tokens: &[Token]) -> String {
+    let err = &entry.error;
+    let location = match &err.location {
+        Some(location) => format!(
+            "{}:{}:{}",
+            escape_xml(&location.file),
+            location.line,
+            location.column
+        ),
+        None => "global".to_string(),
+    };
+    let code = escape_xml(&err.code.to_string());
+    let mut html = format!(
+        "<article class=\"{}\" data-code=\"{}\">\n<header><span class=\"code\">{}</span> \
+         <span class=\"location\">{}</span></header>\n",
+        err.severity.as_str(),
+        code,
+        code,
+        location
+    );
+    html.push_str(&format!(
+        "<p class=\"message\">{}</p>\n",
+        escape_xml(&err.message)
+    ));
+
+    let suggestions = entry
+        .suggestion
+        .as_ref()
+        .map(|s| s.plain_suggestions())
+        .unwrap_or_default();
+
+    if !src.is_empty() {
+        html.push_str("<pre>");
+        for (number, line) in highlight_excerpt(src, tokens, err.line(), entry.span.clone()) {
+            let class = if number == err.line() {
+                "line error"
+            } else {
+                "line"
+            };
+            html.push_str(&format!(
+                "<span class=\"{}\"><span class=\"gutter\">{}</span>{}</span>",
+                class, number, line
+            ));
+            if number == err.line() {
+                for suggestion in &suggestions {
+                    html.push_str(&format!(
+                        "<span class=\"annotation\">^ {}</span>",
+                        escape_xml(suggestion)
+                    ));
+                }
+            }
+        }
+        html.push_str("</pre>\n");
+    } else if !suggestions.is_empty() {
+        html.push_str("<ul>\n");
+        for suggestion in &suggestions {
+            html.push_str(&format!("<li>{}</li>\n", escape_xml(suggestion)));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    if let Some(help) = entry.suggestion.as_ref().and_then(|s| s.plain_help()) {
+        html.push_str(&format!(
+            "<p class=\"help\">Help: {}</p>\n",
+            escape_xml(&help)
+        ));
+    }
+
+    html.push_str("</article>\n");
+    html
+}
+
+/// Highlight the lines around `line`, returning the line number and its HTML for each line. The
+/// error span is wrapped in a `<mark>`.
+fn highlight_excerpt(
+    src: &str, tokens: &[Token], line: usize, span: Option<Range<usize>>,
+) -> Vec<(usize, String)> {
+    let first = line.saturating_sub(CONTEXT).max(1);
+    let last = line + CONTEXT;
+    let span = span.unwrap_or(0..0);
+
+    let mut lines = Vec::new();
+    let mut offset = 0;
+
+    for (i, text) in src.split_inclusive('\n').enumerate() {
+        let number = i + 1;
+        let range = offset..offset + text.trim_end_matches(['\n', '\r']).len();
+        offset += text.len();
+
+        if number < first {
+            continue;
+        }
+        if number > last {
+            break;
+        }
+
+        lines.push((number, highlight_range(src, tokens, range, &span)));
+    }
+
+    lines
+}
+
+/// Highlight a single line of source, it is split into segments at every token and span boundary
+fn highlight_range(
+    src: &str, tokens: &[Token], range: Range<usize>, span: &Range<usize>,
+) -> String {
+    let line_tokens: Vec<&Token> = tokens
+        .iter()
+        .filter(|t| t.start < range.end && t.end > range.start)
+        .collect();
+
+    let mut boundaries = vec![range.start, range.end];
+    for token in &line_tokens {
+        boundaries.push(token.start.clamp(range.start, range.end));
+        boundaries.push(token.end.clamp(range.start, range.end));
+    }
+    boundaries.push(span.start.clamp(range.start, range.end));
+    boundaries.push(span.end.clamp(range.start, range.end));
+    boundaries.sort_unstable();
+    boundaries.dedup();
+
+    let mut html = String::new();
+    for window in boundaries.windows(2) {
+        let (start, end) = (window[0], window[1]);
+        let Some(text) = src.get(start..end) else {
+            continue;
+        };
+
+        let class = line_tokens
+            .iter()
+            .find(|t| t.start <= start && t.end >= end)
+            .and_then(|t| match t.kind {
+                TokenKind::Keyword => Some("kw"),
+                TokenKind::Literal => Some("lit"),
+                TokenKind::Identifier => Some("id"),
+                _ => None,
+            });
+
+        let mut segment = escape_xml(text);
+        if let Some(class) = class {
+            segment = format!("<span class=\"{}\">{}</span>", class, segment);
+        }
+        if start >= span.start && end <= span.end {
+            segment = format!("<mark>{}</mark>", segment);
+        }
+        html.push_str(&segment);
+    }
+
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::Tokenizer;
+
+    #[test]
+    fn test_highlight_marks_span() {
+        let src = "let a: number = \"x\";\nconst b = 1;\n";
+        let tokens = Tokenizer::new(src.to_string()).tokenize();
+
+        let lines = highlight_excerpt(src, &tokens, 1, Some(4..5));
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0].1,
+            "<span class=\"kw\">let</span> <mark><span class=\"id\">a</span></mark>: \
+             <span class=\"id\">number</span> = <span class=\"lit\">&quot;x&quot;</span>;"
+        );
+        assert_eq!(lines[1].0, 2);
+    }
+}
@@ -0,0 +1,70 @@
+use serde::Serialize;
+
+use super::{
+    Entry,
+    Report,
+};
+
+#[derive(Serialize)]
+struct Document<'a> {
+    diagnostics: &'a [Entry],
+    total:       usize,
+}
+
+/// Render the report as a JSON document
+pub fn render(report: &Report) -> anyhow::Result<String> {
+    let doc = Document {
+        diagnostics: &report.entries,
+        total:       report.entries.len(),
+    };
+
+    Ok(serde_json::to_string_pretty(&doc)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        error::{
+            TsError,
+            codes::ErrorCode,
+        },
+        suggestion::Suggestion,
+    };
+
+    #[test]
+    fn test_render_has_no_ansi() {
+        let mut report = Report::default();
+        report.push(TsError::new(
+            "does-not-exist.ts",
+            1,
+            7,
+            ErrorCode::TypeMismatch,
+            "Type 'string' is not assignable to type 'number'.",
+        ));
+        // Styled the way suggestions are when printed to a terminal
+        report.entries[0].suggestion = Some(Suggestion {
+            suggestions: vec!["Change the type to \x1b[1;32m`number`\x1b[0m".to_string()],
+            help:        Some(
+                "\x1b[1mEnsure that the types are compatible or perform an explicit conversion.\x1b[0m"
+                    .to_string(),
+            ),
+            span:        None,
+        });
+
+        let out = render(&report).unwrap();
+        assert!(!out.contains('\x1b'));
+
+        let value: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(value["total"], 1);
+        assert_eq!(value["diagnostics"][0]["code"], "TS2322");
+        assert_eq!(
+            value["diagnostics"][0]["suggestion"]["suggestions"][0],
+            "Change the type to `number`"
+        );
+        assert_eq!(
+            value["diagnostics"][0]["suggestion"]["help"],
+            "Ensure that the types are compatible or perform an explicit conversion."
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use super::{
+    Entry,
+    Report,
+    escape_xml,
+    escape_xml_attr,
+};
+
+/// Render the report as JUnit XML where every checked file is a testcase and every diagnostic in
+/// it a failure. Global diagnostics are failures of an extra `tsc` testcase.
+pub fn render(report: &Report) -> String {
+    let mut files = report.by_file();
+    let global = report.global();
+    if !global.is_empty() {
+        files.insert(0, ("tsc", global));
+    }
+    let tests = files.len();
+    let failures = files
+        .iter()
+        .filter(|(_, entries)| !entries.is_empty())
+        .count();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"ts-analyzer\" tests=\"{}\" failures=\"{}\" errors=\"0\">\n",
+        tests, failures
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"typescript\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"0\">\n",
+        tests, failures
+    ));
+
+    for (file, entries) in &files {
+        let name = escape_xml_attr(file);
+        if entries.is_empty() {
+            xml.push_str(&format!(
+                "    <testcase name=\"{}\" classname=\"typescript\"/>\n",
+                name
+            ));
+            continue;
+        }
+
+        xml.push_str(&format!(
+            "    <testcase name=\"{}\" classname=\"typescript\">\n",
+            name
+        ));
+        for entry in entries {
+            xml.push_str(&failure(entry));
+        }
+        xml.push_str("    </testcase>\n");
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+fn failure(entry: &Entry) -> String {
+    let err = &entry.error;
+    let mut body = match &err.location {
+        Some(location) => format!(
+            "{}:{}:{}\n{}",
+            location.file, location.line, location.column, err.message
+        ),
+        None => err.message.clone(),
+    };
+
+    if let Some(suggestion) = &entry.suggestion {
+        for line in suggestion.plain_suggestions() {
+            body.push_str(&format!("\n- {}", line));
+        }
+        if let Some(help) = suggestion.plain_help() {
+            body.push_str(&format!("\nHelp: {}", help));
+        }
+    }
+
+    format!(
+        "      <failure type=\"{}\" message=\"{}\">{}</failure>\n",
+        escape_xml_attr(&err.code.to_string()),
+        escape_xml_attr(&err.message),
+        escape_xml(&body)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{
+        TsError,
+        codes::ErrorCode,
+    };
+
+    #[test]
+    fn test_render_junit() {
+        let mut report = Report {
+            files: vec!["src/clean.ts".to_string(), "src/index.ts".to_string()],
+            ..Default::default()
+        };
+        report.push(TsError::new(
+            "src/index.ts",
+            3,
+            5,
+            ErrorCode::Unsupported(2769),
+            "No overload matches this call for 'Array<T>' & \"x\".",
+        ));
+
+        let xml = render(&report);
+
+        assert!(xml.contains("tests=\"2\" failures=\"1\""));
+        assert!(xml.contains("<testcase name=\"src/clean.ts\" classname=\"typescript\"/>"));
+        assert!(xml.contains(
+            "<failure type=\"TS2769\" message=\"No overload matches this call for &apos;Array&lt;T&gt;&apos; &amp; &quot;x&quot;.\">"
+        ));
+        assert!(xml.contains(">src/index.ts:3:5\n"));
+    }
+
+    #[test]
+    fn test_render_global_testcase() {
+        let mut report = Report::default();
+        report.push(TsError::global(
+            ErrorCode::Unsupported(18003),
+            "No inputs were found in config file 'tsconfig.json'.",
+        ));
+
+        let xml = render(&report);
+
+        assert!(xml.contains("tests=\"1\" failures=\"1\""));
+        assert!(xml.contains("<testcase name=\"tsc\" classname=\"typescript\">"));
+        assert!(xml.contains("<failure type=\"TS18003\""));
+    }
+
+    #[test]
+    fn test_escape_lint_rule() {
+        let mut report = Report::default();
+        report.push(TsError::new(
+            "does-not-exist.ts",
+            1,
+            1,
+            ErrorCode::Lint("local/no-\"<any>\"".to_string()),
+            "Unexpected any.",
+        ));
+
+        let xml = render(&report);
+
+        assert!(xml.contains("<failure type=\"local/no-&quot;&lt;any&gt;&quot;\""));
+    }
+
+    #[test]
+    fn test_keep_newlines_in_message_attribute() {
+        let mut report = Report::default();
+        report.push(TsError::new(
+            "src/index.ts",
+            1,
+            7,
+            ErrorCode::TypeMismatch,
+            "Type 'string' is not assignable to type 'number'.\n  Types are incompatible.",
+        ));
+
+        let xml = render(&report);
+
+        assert!(xml.contains(
+            "message=\"Type &apos;string&apos; is not assignable to type &apos;number&apos;.&#10;  Types are incompatible.\">"
+        ));
+    }
+}
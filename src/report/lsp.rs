@@ -0,0 +1,216 @@
+use serde_json::{
+    Value,
+    json,
+};
+
+use super::{
+    Entry,
+    Position,
+    Report,
+};
+use crate::{
+    error::Severity,
+    formatter,
+};
+
+/// Render the report as a JSON array of LSP `Diagnostic` objects
+pub fn render(report: &Report) -> anyhow::Result<String> {
+    let diagnostics: Vec<Value> = report.entries.iter().map(diagnostic).collect();
+    Ok(serde_json::to_string_pretty(&diagnostics)?)
+}
+
+/// Build an LSP `Diagnostic` for a single entry
+pub fn diagnostic(entry: &Entry) -> Value {
+    let err = &entry.error;
+    let src = err
+        .file()
+        .and_then(|file| std::fs::read_to_string(file).ok())
+        .unwrap_or_default();
+
+    // Global diagnostics end up at the start of whatever document the client attaches them to
+    let start = entry.start.unwrap_or(Position {
+        line:   err.line().max(1),
+        column: err.column().max(1),
+    });
+    let end = entry.end.unwrap_or(Position {
+        line:   start.line,
+        column: start.column + 1,
+    });
+    let range = json!({
+        "start": lsp_position(&src, start),
+        "end": lsp_position(&src, end),
+    });
+
+    let suggestions = entry
+        .suggestion
+        .as_ref()
+        .map(|s| s.plain_suggestions())
+        .unwrap_or_default();
+    let help = entry.suggestion.as_ref().and_then(|s| s.plain_help());
+
+    let mut message = err.message.clone();
+    if !suggestions.is_empty() {
+        message.push('\n');
+        for suggestion in &suggestions {
+            message.push_str(&format!("\n{}", suggestion));
+        }
+    }
+    if let Some(help) = &help {
+        message.push_str(&format!("\nHelp: {}", help));
+    }
+
+    let related: Vec<Value> = match err.file() {
+        Some(file) => {
+            let uri = file_uri(file);
+            suggestions
+                .iter()
+                .map(|suggestion| {
+                    json!({
+                        "location": { "uri": uri, "range": range },
+                        "message": suggestion,
+                    })
+                })
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    let code = err.code.to_string();
+    let number = code.strip_prefix("TS").and_then(|n| n.parse::<u32>().ok());
+
+    let mut diagnostic = json!({
+        "range": range,
+        "severity": match err.severity {
+            Severity::Error => 1,
+            Severity::Warning => 2,
+            Severity::Message => 3,
+        },
+        "code": number.map(Value::from).unwrap_or_else(|| Value::from(code.clone())),
+        "source": "ts-analyzer",
+        "message": message,
+        "relatedInformation": related,
+        "data": {
+            "originalMessage": err.message,
+            "suggestions": suggestions,
+            "help": help,
+        },
+    });
+
+    // A `TextEdit` the client can offer as a code action
+    if let Some(fix) = &err.fix {
+        let position = |offset| {
+            let (line, column) = formatter::position_at(&src, offset);
+            lsp_position(&src, Position { line, column })
+        };
+        diagnostic["data"]["fix"] = json!({
+            "range": {
+                "start": position(fix.range.start),
+                "end": position(fix.range.end),
+            },
+            "newText": fix.text,
+        });
+    }
+
+    if let Some(number) = number {
+        diagnostic["codeDescription"] = json!({
+            "href": format!("https://typescript.tv/errors/#ts{}", number),
+        });
+    }
+
+    diagnostic
+}
+
+/// LSP positions are 0-indexed and count UTF-16 code units
+fn lsp_position(src: &str, position: Position) -> Value {
+    let line = position.line.saturating_sub(1);
+    let character = src
+        .lines()
+        .nth(line)
+        .map(|text| {
+            text.chars()
+                .take(position.column.saturating_sub(1))
+                .map(char::len_utf16)
+                .sum()
+        })
+        .unwrap_or(position.column.saturating_sub(1));
+
+    json!({ "line": line, "character": character })
+}
+
+fn file_uri(file: &str) -> String {
+    let path = std::path::Path::new(file);
+    let absolute = std::fs::canonicalize(path)
+        .or_else(|_| std::env::current_dir().map(|cwd| cwd.join(path)))
+        .unwrap_or_else(|_| path.to_path_buf());
+
+    let mut uri = String::from("file://");
+    let path = absolute.to_string_lossy().replace('\\', "/");
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' | b':' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{
+        TsError,
+        codes::ErrorCode,
+    };
+
+    #[test]
+    fn test_lsp_position_counts_utf16() {
+        let src = "const s = \"😀\"; let x = 1;\n";
+        assert_eq!(
+            lsp_position(
+                src,
+                Position {
+                    line:   1,
+                    column: 18,
+                }
+            ),
+            json!({ "line": 0, "character": 18 })
+        );
+    }
+
+    #[test]
+    fn test_diagnostic() {
+        let entry = Entry::new(TsError::new(
+            "does-not-exist.ts",
+            2,
+            7,
+            ErrorCode::DeclaredButNeverUsed,
+            "'foo' is declared but its value is never read.",
+        ));
+
+        let diagnostic = diagnostic(&entry);
+
+        assert_eq!(
+            diagnostic["range"]["start"],
+            json!({ "line": 1, "character": 6 })
+        );
+        assert_eq!(diagnostic["severity"], 2);
+        assert_eq!(diagnostic["code"], 6133);
+        assert_eq!(diagnostic["source"], "ts-analyzer");
+        assert_eq!(diagnostic["data"]["suggestions"][0], "`foo` is unused");
+        assert_eq!(
+            diagnostic["relatedInformation"][0]["message"],
+            "`foo` is unused"
+        );
+        assert!(
+            diagnostic["relatedInformation"][0]["location"]["uri"]
+                .as_str()
+                .unwrap()
+                .starts_with("file:///")
+        );
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+
+use super::{
+    Entry,
+    Report,
+    escape_markdown,
+    escape_xml,
+    excerpt,
+};
+
+/// Rows in the per file summary table before the rest is folded into a single row
+const MAX_SUMMARY_FILES: usize = 50;
+
+/// GitHub rejects comments over 65536 characters, leave some room for whatever wraps the report
+const MAX_LEN: usize = 60_000;
+
+/// Render the report as Markdown with a summary table and a collapsible section per file. Global
+/// diagnostics get a section of their own in front of the files.
+pub fn render(report: &Report) -> String {
+    let files: Vec<(&str, Vec<&Entry>)> = report
+        .by_file()
+        .into_iter()
+        .filter(|(_, entries)| !entries.is_empty())
+        .collect();
+    let global = report.global();
+
+    if files.is_empty() && global.is_empty() {
+        return "## ts-analyzer\n\nNo errors were emitted.\n".to_string();
+    }
+
+    let mut out = format!(
+        "## ts-analyzer\n\nFound **{}** {} in **{}** {}.\n\n",
+        report.entries.len(),
+        plural(report.entries.len(), "error", "errors"),
+        files.len(),
+        plural(files.len(), "file", "files"),
+    );
+    out.push_str(&summary(report, &files));
+
+    let sections = (!global.is_empty())
+        .then_some((None, global))
+        .into_iter()
+        .chain(
+            files
+                .into_iter()
+                .map(|(file, entries)| (Some(file), entries)),
+        );
+
+    let mut shown = 0;
+    'files: for (file, entries) in sections {
+        let (header, src) = match file {
+            Some(file) => (
+                format!(
+                    "<details>\n<summary><code>{}</code> ({})</summary>\n\n",
+                    escape_xml(file),
+                    entries.len()
+                ),
+                std::fs::read_to_string(file).unwrap_or_default(),
+            ),
+            None => (
+                format!(
+                    "<details open>\n<summary>Global ({})</summary>\n\n",
+                    entries.len()
+                ),
+                String::new(),
+            ),
+        };
+        let mut section = String::new();
+
+        for entry in &entries {
+            let block = entry_block(entry, &src);
+            let footer_room = "</details>\n\n".len() + omitted_line(usize::MAX).len();
+            if out.len() + header.len() + section.len() + block.len() + footer_room > MAX_LEN {
+                if !section.is_empty() {
+                    out.push_str(&header);
+                    out.push_str(&section);
+                    out.push_str("</details>\n\n");
+                }
+                break 'files;
+            }
+            section.push_str(&block);
+            shown += 1;
+        }
+
+        out.push_str(&header);
+        out.push_str(&section);
+        out.push_str("</details>\n\n");
+    }
+
+    let omitted = report.entries.len() - shown;
+    if omitted > 0 {
+        out.push_str(&omitted_line(omitted));
+    }
+
+    out
+}
+
+/// Counts per error code and per file
+fn summary(report: &Report, files: &[(&str, Vec<&Entry>)]) -> String {
+    let mut codes: HashMap<String, usize> = HashMap::new();
+    for entry in &report.entries {
+        *codes.entry(entry.error.code.to_string()).or_default() += 1;
+    }
+    let mut codes: Vec<(String, usize)> = codes.into_iter().collect();
+    codes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let mut out = String::from("| Code | Count |\n| --- | ---: |\n");
+    for (code, count) in codes {
+        out.push_str(&format!("| `{}` | {} |\n", code, count));
+    }
+
+    let mut files: Vec<(&str, usize)> = files
+        .iter()
+        .map(|(file, entries)| (*file, entries.len()))
+        .collect();
+    files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    out.push_str("\n| File | Errors |\n| --- | ---: |\n");
+    for (file, count) in files.iter().take(MAX_SUMMARY_FILES) {
+        out.push_str(&format!("| `{}` | {} |\n", file.replace('|', "\\|"), count));
+    }
+    if files.len() > MAX_SUMMARY_FILES {
+        let rest = &files[MAX_SUMMARY_FILES..];
+        out.push_str(&format!(
+            "| _{} more files_ | {} |\n",
+            rest.len(),
+            rest.iter().map(|(_, count)| count).sum::<usize>()
+        ));
+    }
+    out.push('\n');
+
+    out
+}
+
+fn entry_block(entry: &Entry, src: &str) -> String {
+    let err = &entry.error;
+    let mut block = match &err.location {
+        Some(location) => format!(
+            "**{}** at line {}, column {}\n\n",
+            escape_markdown(&err.code.to_string()),
+            location.line,
+            location.column
+        ),
+        None => format!("**{}**\n\n", escape_markdown(&err.code.to_string())),
+    };
+
+    for line in err.message.lines() {
+        block.push_str(&format!("> {}\n", escape_markdown(line.trim())));
+    }
+    block.push('\n');
+
+    let lines = excerpt(src, err.line(), 2);
+    if !lines.is_empty() {
+        block.push_str("```ts\n");
+        for (number, text) in lines {
+            block.push_str(text);
+            block.push('\n');
+            if number == err.line() {
+                block.push_str(&marker(entry, text));
+            }
+        }
+        block.push_str("```\n\n");
+    }
+
+    if let Some(suggestion) = &entry.suggestion {
+        for line in suggestion.plain_suggestions() {
+            block.push_str(&format!("- {}\n", line));
+        }
+        if let Some(help) = suggestion.plain_help() {
+            block.push_str(&format!("\n**Help:** {}\n", help));
+        }
+        block.push('\n');
+    }
+
+    block
+}
+
+/// A `^^^` line under the span of the error
+fn marker(entry: &Entry, text: &str) -> String {
+    let line = entry.error.line();
+    let start = entry
+        .start
+        .filter(|start| start.line == line)
+        .map(|start| start.column)
+        .unwrap_or(entry.error.column());
+    let end = entry
+        .end
+        .filter(|end| end.line == line && end.column > start)
+        .map(|end| end.column)
+        .unwrap_or(start + 1);
+
+    // Keep tabs so the marker lines up with the source line
+    let indent: String = text
+        .chars()
+        .take(start.saturating_sub(1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+
+    format!("{}{}\n", indent, "^".repeat(end - start))
+}
+
+fn omitted_line(omitted: usize) -> String {
+    format!(
+        "_{} more {} omitted._\n",
+        omitted,
+        plural(omitted, "error", "errors")
+    )
+}
+
+fn plural<'a>(count: usize, one: &'a str, many: &'a str) -> &'a str {
+    if count == 1 { one } else { many }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{
+        TsError,
+        codes::ErrorCode,
+    };
+
+    fn error(file: &str, line: usize) -> TsError {
+        TsError::new(
+            file,
+            line,
+            1,
+            ErrorCode::Unsupported(2769),
+            "No overload matches this call for 'Array<T>'.",
+        )
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let mut report = Report::default();
+        report.push(error("src/a.ts", 1));
+        report.push(error("src/a.ts", 2));
+        report.push(error("src/b.ts", 1));
+
+        let md = render(&report);
+
+        assert!(md.contains("Found **3** errors in **2** files."));
+        assert!(md.contains("| `TS2769` | 3 |"));
+        assert!(md.contains("| `src/a.ts` | 2 |"));
+        assert!(md.contains("<summary><code>src/b.ts</code> (1)</summary>"));
+        assert!(md.contains("> No overload matches this call for 'Array\\<T\\>'."));
+        assert!(!md.contains("omitted"));
+    }
+
+    #[test]
+    fn test_render_markdown_global() {
+        let mut report = Report::default();
+        report.push(TsError::global(
+            ErrorCode::Unsupported(18003),
+            "No inputs were found in config file 'tsconfig.json'.",
+        ));
+
+        let md = render(&report);
+
+        assert!(!md.contains("No errors were emitted."));
+        assert!(md.contains("<details open>\n<summary>Global (1)</summary>\n\n**TS18003**\n\n"));
+    }
+
+    #[test]
+    fn test_render_markdown_is_capped() {
+        let mut report = Report::default();
+        for line in 0..2000 {
+            report.push(error("src/a.ts", line + 1));
+        }
+
+        let md = render(&report);
+
+        assert!(md.len() <= MAX_LEN);
+        assert!(md.trim_end().ends_with("more errors omitted._"));
+        assert!(md.contains("</details>"));
+    }
+}
@@ -0,0 +1,256 @@
+use std::{
+    ops::Range,
+    path::PathBuf,
+};
+
+use serde::Serialize;
+
+use crate::{
+    error::TsError,
+    formatter,
+    suggestion::Suggestion,
+};
+
+pub mod checkstyle;
+pub mod github;
+pub mod gitlab;
+pub mod html;
+pub mod json;
+pub mod junit;
+pub mod lsp;
+pub mod markdown;
+pub mod sarif;
+pub mod short;
+
+/// Output format of the diagnostics report
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Annotated source excerpts for the terminal
+    #[default]
+    Pretty,
+    /// One line per diagnostic for quickfix lists and `errorformat`
+    Short,
+    /// JSON document with every diagnostic and its suggestion
+    Json,
+    /// SARIF 2.1.0 log for code scanning dashboards
+    Sarif,
+    /// GitHub Actions workflow commands that annotate the pull request
+    Github,
+    /// JUnit XML with one testcase per checked file
+    Junit,
+    /// Checkstyle XML for static analysis aggregators
+    Checkstyle,
+    /// Markdown summary for pull request comments
+    Markdown,
+    /// Self-contained HTML page
+    Html,
+    /// LSP `Diagnostic` objects, a single object with `--from-lsp`
+    Lsp,
+}
+
+/// A 1-indexed line and column in a source file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Position {
+    pub line:   usize,
+    pub column: usize,
+}
+
+/// A `TsError` together with its suggestion and the span resolved in the source
+#[derive(Debug, Clone, Serialize)]
+pub struct Entry {
+    #[serde(flatten)]
+    pub error:      TsError,
+    pub suggestion: Option<Suggestion>,
+    /// Byte span in the source the report points at
+    pub span:       Option<Range<usize>>,
+    pub start:      Option<Position>,
+    pub end:        Option<Position>,
+}
+
+impl Entry {
+    pub fn new(error: TsError) -> Self {
+        let analysis = formatter::analyze(&error);
+        let span = analysis.label_span();
+        let (start, end) = match &span {
+            Some(span) => {
+                let (line, column) = formatter::position_at(&analysis.src, span.start);
+                let (end_line, end_column) = formatter::position_at(&analysis.src, span.end);
+                (
+                    Some(Position { line, column }),
+                    Some(Position {
+                        line:   end_line,
+                        column: end_column,
+                    }),
+                )
+            }
+            None => (None, None),
+        };
+
+        Self {
+            error,
+            suggestion: analysis.suggestion,
+            span,
+            start,
+            end,
+        }
+    }
+}
+
+/// The checker command that produced the diagnostics
+#[derive(Debug, Clone)]
+pub struct Invocation {
+    pub program:   String,
+    pub args:      Vec<String>,
+    /// `None` when the process was terminated by a signal
+    pub exit_code: Option<i32>,
+    /// What the checker reported for `--version`
+    pub version:   Option<String>,
+}
+
+impl Invocation {
+    pub fn command_line(&self) -> String {
+        std::iter::once(self.program.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// All diagnostics collected from a run
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub entries:    Vec<Entry>,
+    pub invocation: Option<Invocation>,
+    /// Every file the checker looked at, empty when the file list is unknown
+    pub files:      Vec<String>,
+}
+
+impl Report {
+    pub fn push(&mut self, error: TsError) {
+        self.entries.push(Entry::new(error));
+    }
+
+    /// Diagnostics grouped per file. Known files come first, followed by files that only show up
+    /// in diagnostics, in order of appearance. Global diagnostics are left out, see `global`.
+    pub fn by_file(&self) -> Vec<(&str, Vec<&Entry>)> {
+        let mut files: Vec<(&str, Vec<&Entry>)> = self
+            .files
+            .iter()
+            .map(|file| (file.as_str(), Vec::new()))
+            .collect();
+
+        for entry in &self.entries {
+            let Some(path) = entry.error.file() else {
+                continue;
+            };
+            match files.iter_mut().find(|(file, _)| *file == path) {
+                Some((_, entries)) => entries.push(entry),
+                None => files.push((path, vec![entry])),
+            }
+        }
+
+        files
+    }
+
+    /// Diagnostics that aren't tied to a file, like tsconfig or command line errors
+    pub fn global(&self) -> Vec<&Entry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.error.location.is_none())
+            .collect()
+    }
+
+    /// The tsconfig a global diagnostic is attached to in formats that need a file for every
+    /// diagnostic: the project that was built or checked when it exists, `tsconfig.json` otherwise
+    pub fn config_file(&self, entry: &Entry) -> String {
+        let project = entry.error.project.clone().or_else(|| {
+            let args = &self.invocation.as_ref()?.args;
+            let flag = args
+                .iter()
+                .position(|arg| matches!(arg.as_str(), "-p" | "--project" | "-b" | "--build"))?;
+            args.get(flag + 1).cloned()
+        });
+
+        let config = match project.map(PathBuf::from) {
+            Some(path) if path.is_dir() => path.join("tsconfig.json"),
+            Some(path) if path.is_file() => path,
+            _ => PathBuf::from("tsconfig.json"),
+        };
+        let config = config.to_string_lossy().replace('\\', "/");
+        config.strip_prefix("./").unwrap_or(&config).to_string()
+    }
+
+    /// Render the report in a machine readable format
+    pub fn render(&self, format: Format) -> anyhow::Result<String> {
+        match format {
+            Format::Pretty => Ok(self
+                .entries
+                .iter()
+                .map(|entry| formatter::fmt(&entry.error))
+                .collect::<Vec<_>>()
+                .join("\n")),
+            Format::Short => Ok(short::render(self)),
+            Format::Json => json::render(self),
+            Format::Sarif => sarif::render(self),
+            Format::Github => Ok(github::render(self)),
+            Format::Junit => Ok(junit::render(self)),
+            Format::Checkstyle => Ok(checkstyle::render(self)),
+            Format::Markdown => Ok(markdown::render(self)),
+            Format::Html => Ok(html::render(self)),
+            Format::Lsp => lsp::render(self),
+        }
+    }
+}
+
+/// Escape text for use in XML character data and HTML. Control characters other than tab and
+/// newlines are not allowed in XML 1.0 and are dropped.
+fn escape_xml(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Escape text for use in XML attribute values. Parsers normalise tabs and newlines in attributes
+/// to spaces, they are written as character references to survive that.
+fn escape_xml_attr(s: &str) -> String {
+    escape_xml(s)
+        .replace('\t', "&#9;")
+        .replace('\n', "&#10;")
+        .replace('\r', "&#13;")
+}
+
+/// Escape characters in tsc messages that markdown renderers would interpret, e.g. in `Array<T>`
+fn escape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '<' | '>' | '[' | ']' | '#') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// The lines around `line` (1-indexed) together with their line numbers
+fn excerpt(src: &str, line: usize, context: usize) -> Vec<(usize, &str)> {
+    let first = line.saturating_sub(context).max(1);
+    let last = line + context;
+
+    src.lines()
+        .enumerate()
+        .map(|(i, text)| (i + 1, text))
+        .skip(first - 1)
+        .take(last + 1 - first)
+        .collect()
+}
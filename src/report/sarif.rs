@@ -0,0 +1,256 @@
+use serde_json::{
+    Value,
+    json,
+};
+
+use super::{
+    Entry,
+    Report,
+    escape_markdown,
+};
+use crate::error::{
+    Severity,
+    codes::ErrorCode,
+};
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const INFORMATION_URI: &str = "https://github.com/mikkurogue/ts-analyzer";
+
+/// Render the report as a SARIF 2.1.0 log
+pub fn render(report: &Report) -> anyhow::Result<String> {
+    let mut codes: Vec<ErrorCode> = Vec::new();
+    for entry in &report.entries {
+        if !codes.contains(&entry.error.code) {
+            codes.push(entry.error.code.clone());
+        }
+    }
+
+    let rules: Vec<Value> = codes.iter().map(|code| rule(code, report)).collect();
+    let results: Vec<Value> = report
+        .entries
+        .iter()
+        .map(|entry| {
+            let rule_index = codes.iter().position(|c| *c == entry.error.code);
+            result(entry, rule_index.unwrap_or_default())
+        })
+        .collect();
+
+    let mut run = json!({
+        "tool": {
+            "driver": {
+                "name": "ts-analyzer",
+                "version": env!("CARGO_PKG_VERSION"),
+                "informationUri": INFORMATION_URI,
+                "rules": rules,
+            }
+        },
+        "results": results,
+    });
+
+    if let Some(invocation) = &report.invocation {
+        run["invocations"] = json!([{
+            "commandLine": invocation.command_line(),
+            "arguments": invocation.args,
+            "exitCode": invocation.exit_code,
+            "executionSuccessful": invocation.exit_code.is_some(),
+        }]);
+    }
+
+    let log = json!({
+        "$schema": SCHEMA,
+        "version": "2.1.0",
+        "runs": [run],
+    });
+
+    Ok(serde_json::to_string_pretty(&log)?)
+}
+
+/// Build the rule descriptor for an error code. Help texts mention the names in the diagnostic, the
+/// rule only gets one when it is the same for every diagnostic with that code.
+fn rule(code: &ErrorCode, report: &Report) -> Value {
+    let mut rule = json!({
+        "id": code.to_string(),
+        "name": variant_name(code),
+    });
+
+    let mut helps = report
+        .entries
+        .iter()
+        .filter(|entry| entry.error.code == *code)
+        .map(|entry| entry.suggestion.as_ref().and_then(|s| s.plain_help()));
+    let first = helps.next().flatten();
+
+    if let Some(help) = first
+        && helps.all(|other| other.as_ref() == Some(&help))
+    {
+        rule["help"] = json!({ "text": help });
+    }
+
+    rule
+}
+
+fn result(entry: &Entry, rule_index: usize) -> Value {
+    let err = &entry.error;
+    let mut markdown = escape_markdown(&err.message);
+    if let Some(suggestion) = &entry.suggestion {
+        for line in suggestion.plain_suggestions() {
+            markdown.push_str(&format!("\n\n- {}", line));
+        }
+        if let Some(help) = suggestion.plain_help() {
+            markdown.push_str(&format!("\n\nHelp: {}", help));
+        }
+    }
+
+    // Global diagnostics are results without locations
+    let locations: Vec<Value> = err
+        .location
+        .iter()
+        .map(|location| {
+            let region = match (entry.start, entry.end) {
+                (Some(start), Some(end)) => json!({
+                    "startLine": start.line,
+                    "startColumn": start.column,
+                    "endLine": end.line,
+                    "endColumn": end.column,
+                }),
+                _ => json!({
+                    "startLine": location.line,
+                    "startColumn": location.column,
+                }),
+            };
+            json!({
+                "physicalLocation": {
+                    "artifactLocation": artifact_location(&location.file),
+                    "region": region,
+                }
+            })
+        })
+        .collect();
+
+    let mut result = json!({
+        "ruleId": err.code.to_string(),
+        "ruleIndex": rule_index,
+        "level": match err.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Message => "note",
+        },
+        "message": {
+            "text": err.message,
+            "markdown": markdown,
+        },
+        "locations": locations,
+    });
+
+    if let (Some(fix), Some(file)) = (&err.fix, err.file()) {
+        result["fixes"] = json!([{
+            "description": { "text": "Autofix" },
+            "artifactChanges": [{
+                "artifactLocation": artifact_location(file),
+                "replacements": [{
+                    "deletedRegion": {
+                        "byteOffset": fix.range.start,
+                        "byteLength": fix.range.len(),
+                    },
+                    "insertedContent": { "text": fix.text },
+                }],
+            }],
+        }]);
+    }
+
+    result
+}
+
+/// Relative paths are resolved against the source root, absolute paths become `file://` URIs
+fn artifact_location(file: &str) -> Value {
+    let uri = file.replace('\\', "/");
+    if uri.starts_with('/') {
+        json!({ "uri": format!("file://{}", uri) })
+    } else {
+        json!({ "uri": uri, "uriBaseId": "%SRCROOT%" })
+    }
+}
+
+/// The enum variant name of the code, e.g. `TypeMismatch`
+fn variant_name(code: &ErrorCode) -> String {
+    let debug = format!("{:?}", code);
+    debug.split('(').next().unwrap_or_default().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        error::TsError,
+        report::Invocation,
+    };
+
+    #[test]
+    fn test_render_sarif() {
+        let mut report = Report {
+            invocation: Some(Invocation {
+                program:   "tsc".to_string(),
+                args:      vec!["--noEmit".to_string()],
+                exit_code: Some(2),
+                version:   None,
+            }),
+            ..Default::default()
+        };
+        report.push(TsError::new(
+            "src/index.ts",
+            3,
+            5,
+            ErrorCode::TypeMismatch,
+            "Type 'string' is not assignable to type 'number'.",
+        ));
+        report.push(TsError::new(
+            "src/other.ts",
+            1,
+            1,
+            ErrorCode::Unsupported(9999),
+            "Something else.",
+        ));
+        report.push(TsError::global(
+            ErrorCode::Unsupported(18003),
+            "No inputs were found in config file 'tsconfig.json'.",
+        ));
+
+        let log: Value = serde_json::from_str(&render(&report).unwrap()).unwrap();
+        let run = &log["runs"][0];
+
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "TS2322");
+        assert_eq!(run["tool"]["driver"]["rules"][0]["name"], "TypeMismatch");
+        assert_eq!(run["tool"]["driver"]["rules"][1]["id"], "TS9999");
+        assert_eq!(run["tool"]["driver"]["rules"][1]["name"], "Unsupported");
+        assert_eq!(run["results"][1]["ruleIndex"], 1);
+        assert_eq!(
+            run["results"][0]["locations"][0]["physicalLocation"]["region"]["startLine"],
+            3
+        );
+        assert_eq!(run["results"][2]["locations"], json!([]));
+        assert_eq!(run["invocations"][0]["commandLine"], "tsc --noEmit");
+        assert_eq!(run["invocations"][0]["exitCode"], 2);
+    }
+
+    #[test]
+    fn test_rule_help_is_shared_by_every_result() {
+        let mut report = Report::default();
+        for (line, name) in [(1, "foo"), (2, "bar")] {
+            report.push(TsError::new(
+                "src/index.ts",
+                line,
+                7,
+                ErrorCode::DeclaredButNeverUsed,
+                format!("'{}' is declared but its value is never read.", name),
+            ));
+        }
+
+        let log: Value = serde_json::from_str(&render(&report).unwrap()).unwrap();
+        let run = &log["runs"][0];
+
+        assert_eq!(run["tool"]["driver"]["rules"][0]["help"], Value::Null);
+        let markdown = run["results"][1]["message"]["markdown"].as_str().unwrap();
+        assert!(markdown.contains("Help: Consider removing the reference to `bar`"));
+    }
+}
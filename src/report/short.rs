@@ -0,0 +1,94 @@
+use super::{
+    Entry,
+    Report,
+};
+
+/// Render the report with one line per diagnostic:
+/// `path:line:col: error TS2322: <message> [hint: <first suggestion>]`, global diagnostics have no
+/// `path:line:col: ` prefix
+pub fn render(report: &Report) -> String {
+    report
+        .entries
+        .iter()
+        .map(line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn line(entry: &Entry) -> String {
+    let err = &entry.error;
+    let mut line = String::new();
+    if let Some(location) = &err.location {
+        line.push_str(&format!(
+            "{}:{}:{}: ",
+            location.file, location.line, location.column
+        ));
+    }
+    line.push_str(&format!(
+        "{} {}: {}",
+        err.severity.as_str(),
+        err.code,
+        collapse(&err.message)
+    ));
+
+    if let Some(hint) = entry
+        .suggestion
+        .as_ref()
+        .and_then(|s| s.plain_suggestions().into_iter().next())
+    {
+        line.push_str(&format!(" [hint: {}]", collapse(&hint)));
+    }
+
+    line
+}
+
+/// Join multi-line elaborations into a single line
+fn collapse(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{
+        TsError,
+        codes::ErrorCode,
+    };
+
+    #[test]
+    fn test_render_short() {
+        let mut report = Report::default();
+        report.push(TsError::new(
+            "src/index.ts",
+            1,
+            7,
+            ErrorCode::DeclaredButNeverUsed,
+            "'foo' is declared but its value is never read.",
+        ));
+        report.push(TsError::new(
+            "src/index.ts",
+            4,
+            1,
+            ErrorCode::Unsupported(2769),
+            "No overload matches this call.\nOverload 1 of 2, '(x: string): void', gave \
+                      the following error.",
+        ));
+        report.push(TsError::global(
+            ErrorCode::Unsupported(5023),
+            "Unknown compiler option 'foo'.",
+        ));
+
+        assert_eq!(
+            render(&report),
+            "src/index.ts:1:7: warning TS6133: 'foo' is declared but its value is never read. \
+             [hint: `foo` is unused]\n\
+             src/index.ts:4:1: error TS2769: No overload matches this call. Overload 1 of 2, \
+             '(x: string): void', gave the following error.\n\
+             error TS5023: Unknown compiler option 'foo'."
+        );
+    }
+}
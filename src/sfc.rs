@@ -0,0 +1,138 @@
+use std::ops::Range;
+
+use crate::tokenizer::{
+    Token,
+    Tokenizer,
+};
+
+/// Byte ranges of the contents of the `<script lang="ts">` and `<script setup>` blocks in a Vue or
+/// Svelte single-file component
+pub fn script_blocks(src: &str) -> Vec<Range<usize>> {
+    let mut blocks = Vec::new();
+    let mut position = 0;
+
+    while let Some(found) = src[position..].find("<script") {
+        let tag_start = position + found;
+        let Some(tag_len) = tag_end(&src[tag_start..]) else {
+            break;
+        };
+        let attributes = &src[tag_start + "<script".len()..tag_start + tag_len];
+        let content_start = tag_start + tag_len + 1;
+        let content_end = src[content_start..]
+            .find("</script")
+            .map_or(src.len(), |end| content_start + end);
+
+        // `<scripts>` or `<script-foo>` aren't script blocks
+        let is_tag = attributes.is_empty() || attributes.starts_with(char::is_whitespace);
+        if is_tag && is_typescript(attributes) {
+            blocks.push(content_start..content_end);
+        }
+        position = content_end;
+    }
+
+    blocks
+}
+
+/// Position of the `>` that closes the tag at the start of `src`. Attribute values may contain `>`
+/// themselves, e.g. `generic="T extends Record<string, unknown>"`.
+fn tag_end(src: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in src.char_indices() {
+        match (quote, c) {
+            (None, '>') => return Some(i),
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), c) if c == open => quote = None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `setup` scripts are checked as TypeScript by vue-tsc, other blocks only with `lang="ts"`
+fn is_typescript(attributes: &str) -> bool {
+    attributes
+        .split(|c: char| c.is_whitespace() || c == '/')
+        .any(|attribute| {
+            matches!(
+                attribute,
+                "setup" | "lang=\"ts\"" | "lang='ts'" | "lang=\"tsx\"" | "lang='tsx'"
+            )
+        })
+}
+
+/// Tokenize a source file. Only the script blocks of `.vue` and `.svelte` files are tokenized,
+/// template and style markup would produce tokens that match error positions by accident.
+pub fn tokenize(file: &str, src: &str) -> Vec<Token> {
+    if !file.ends_with(".vue") && !file.ends_with(".svelte") {
+        return Tokenizer::new(src.to_string()).tokenize();
+    }
+
+    let mut tokens = Vec::new();
+    for block in script_blocks(src) {
+        // Tokens are positioned relative to the block, shift them into the full file
+        let before = &src[..block.start];
+        let line = before.matches('\n').count();
+        let column = before[before.rfind('\n').map_or(0, |i| i + 1)..]
+            .chars()
+            .count();
+
+        tokens.extend(
+            Tokenizer::new(src[block.clone()].to_string())
+                .tokenize()
+                .into_iter()
+                .map(|mut token| {
+                    if token.line == 1 {
+                        token.column += column;
+                    }
+                    token.line += line;
+                    token.start += block.start;
+                    token.end += block.start;
+                    token
+                }),
+        );
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SFC: &str = "<template>\n  <div :class=\"{ active: isActive }\">{{ count }}</div>\n\
+                       </template>\n\n<script setup lang=\"ts\">\nconst count: number = \
+                       \"zero\";\n</script>\n\n<style>\n.a { color: red; }\n</style>\n";
+
+    #[test]
+    fn test_script_blocks() {
+        let blocks = script_blocks(SFC);
+
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(
+            &SFC[blocks[0].clone()],
+            "\nconst count: number = \"zero\";\n"
+        );
+        assert!(script_blocks("<script>\nvar a = 1;\n</script>").is_empty());
+        assert_eq!(
+            script_blocks("<script lang='ts'>let a = 1</script>"),
+            vec![18..27]
+        );
+
+        let generic = "<script setup lang=\"ts\" generic=\"T extends Record<string, unknown>\">\n\
+                       const a: T = 1;\n</script>";
+        let blocks = script_blocks(generic);
+        assert_eq!(&generic[blocks[0].clone()], "\nconst a: T = 1;\n");
+    }
+
+    #[test]
+    fn test_tokenize_vue_keeps_file_positions() {
+        let tokens = tokenize("App.vue", SFC);
+
+        assert_eq!(tokens[0].raw, "const");
+        assert!(!tokens.iter().any(|token| token.raw == "isActive"));
+
+        let count = tokens.iter().find(|token| token.raw == "count").unwrap();
+        assert_eq!(count.line, 6);
+        assert_eq!(count.column, 6);
+        assert_eq!(&SFC[count.start..count.end], "count");
+    }
+}
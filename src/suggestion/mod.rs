@@ -1,6 +1,38 @@
+use serde::{
+    Serialize,
+    Serializer,
+    ser::SerializeStruct,
+};
+
+use crate::ansi;
+
 #[derive(Debug, Clone)]
 pub struct Suggestion {
     pub suggestions: Vec<String>,
     pub help:        Option<String>,
     pub span:        Option<std::ops::Range<usize>>,
 }
+
+impl Suggestion {
+    /// Suggestion lines without terminal styling
+    pub fn plain_suggestions(&self) -> Vec<String> {
+        self.suggestions.iter().map(|s| ansi::strip(s)).collect()
+    }
+
+    /// Help text without terminal styling
+    pub fn plain_help(&self) -> Option<String> {
+        self.help.as_deref().map(ansi::strip)
+    }
+}
+
+/// Suggestions are built with `colored` styling for the terminal report, so serialized output
+/// always goes through the plain variants.
+impl Serialize for Suggestion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Suggestion", 3)?;
+        state.serialize_field("suggestions", &self.plain_suggestions())?;
+        state.serialize_field("help", &self.plain_help())?;
+        state.serialize_field("span", &self.span)?;
+        state.end()
+    }
+}
@@ -41,8 +41,8 @@ pub fn find_function_name_before(tokens: &[Token], line: usize, column: usize) -
 /// Extract the identifier/token text at the error position
 pub fn extract_identifier_at_error(err: &TsError, tokens: &[Token]) -> Option<String> {
     // Adjust column by -1 to match token indexing
-    let adjusted_column = err.column.saturating_sub(1);
-    find_token_at_position(tokens, err.line, adjusted_column).map(|token| token.raw.clone())
+    let adjusted_column = err.column().saturating_sub(1);
+    find_token_at_position(tokens, err.line(), adjusted_column).map(|token| token.raw.clone())
 }
 
 /// Extract the identifier at error position with a fallback default value
@@ -52,7 +52,7 @@ pub fn extract_identifier_or_default(err: &TsError, tokens: &[Token], default: &
 
 /// Extract function name for parameter-related errors by searching backwards
 pub fn extract_function_name(err: &TsError, tokens: &[Token], default: &str) -> String {
-    find_function_name_before(tokens, err.line, err.column.saturating_sub(1))
+    find_function_name_before(tokens, err.line(), err.column().saturating_sub(1))
         .map(|token| token.raw.clone())
         .unwrap_or_else(|| default.to_string())
 }
@@ -0,0 +1,191 @@
+use std::{
+    path::{
+        Path,
+        PathBuf,
+    },
+    process::Child,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use anyhow::Context;
+use serde_json::{
+    Value,
+    json,
+};
+
+use crate::error::relative_to_cwd;
+
+/// A temporary tsconfig that extends the project config of the checked files and only includes
+/// them, so `paths`, `jsx`, `lib` and friends apply as they do in the project. It is written next
+/// to the project config because relative paths and the `node_modules/@types` lookup are resolved
+/// from the directory of the config, and removed again when dropped.
+pub struct FileConfig {
+    path: PathBuf,
+}
+
+impl FileConfig {
+    /// `None` when the files aren't part of a project with a tsconfig.json. Files of different
+    /// projects would be checked with the wrong options, so they are rejected.
+    pub fn create(names: &[String]) -> anyhow::Result<Option<FileConfig>> {
+        let files = canonicalize(names)?;
+        let configs: Vec<Option<PathBuf>> = files.iter().map(|file| nearest(file)).collect();
+        if let Some(other) = configs.iter().position(|config| *config != configs[0]) {
+            let describe = |config: &Option<PathBuf>| match config {
+                Some(config) => relative_to_cwd(&config.to_string_lossy()),
+                None => "no tsconfig.json".to_string(),
+            };
+            anyhow::bail!(
+                "`{}` ({}) and `{}` ({}) belong to different projects, check them in separate runs",
+                names[0],
+                describe(&configs[0]),
+                names[other],
+                describe(&configs[other])
+            );
+        }
+        let Some(Some(base)) = configs.into_iter().next() else {
+            return Ok(None);
+        };
+
+        let dir = base.parent().unwrap_or(Path::new("."));
+        let path = dir.join(format!("tsconfig.ts-analyzer-{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            serde_json::to_string_pretty(&contents(&base, &files))?,
+        )
+        .with_context(|| format!("Could not write `{}`", path.display()))?;
+
+        Ok(Some(FileConfig { path }))
+    }
+
+    pub fn path(&self) -> String {
+        relative_to_cwd(&self.path.to_string_lossy())
+    }
+}
+
+impl Drop for FileConfig {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Ctrl-C and SIGTERM don't run destructors, so the checker is killed and the temporary tsconfig
+/// removed by a signal handler instead. The checker isn't killed while it is waited on, it is
+/// exiting already.
+pub fn remove_on_signal(
+    file_config: Option<&FileConfig>, child: Arc<Mutex<Child>>,
+) -> anyhow::Result<()> {
+    let path = file_config.map(|config| config.path.clone());
+    ctrlc::set_handler(move || {
+        if let Ok(mut child) = child.try_lock() {
+            let _ = child.kill();
+        }
+        if let Some(path) = &path {
+            let _ = std::fs::remove_file(path);
+        }
+        std::process::exit(130);
+    })
+    .context("Could not install the Ctrl-C handler")
+}
+
+/// The files given on the command line. Diagnostics in other files come from the files they import.
+pub struct CheckedFiles {
+    files: Vec<PathBuf>,
+}
+
+impl CheckedFiles {
+    pub fn new(files: &[String]) -> anyhow::Result<CheckedFiles> {
+        Ok(CheckedFiles {
+            files: canonicalize(files)?,
+        })
+    }
+
+    pub fn contains(&self, file: &str) -> bool {
+        std::fs::canonicalize(file).is_ok_and(|file| self.files.contains(&file))
+    }
+}
+
+/// Absolute paths of the files, which is how they are compared to the files of diagnostics
+fn canonicalize(files: &[String]) -> anyhow::Result<Vec<PathBuf>> {
+    files
+        .iter()
+        .map(|file| {
+            std::fs::canonicalize(file).with_context(|| format!("Could not find `{}`", file))
+        })
+        .collect()
+}
+
+/// The closest tsconfig.json in the directory of `file` or its parents
+fn nearest(file: &Path) -> Option<PathBuf> {
+    file.parent()?
+        .ancestors()
+        .map(|dir| dir.join("tsconfig.json"))
+        .find(|config| config.is_file())
+}
+
+fn contents(base: &Path, files: &[PathBuf]) -> Value {
+    let name = base
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+
+    json!({
+        "extends": format!("./{}", name),
+        "files": files,
+        // Otherwise `include` is inherited from the project config
+        "include": [],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_config_extends_nearest_tsconfig() {
+        let root =
+            std::env::temp_dir().join(format!("ts-analyzer-tsconfig-{}", std::process::id()));
+        let src = root.join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(root.join("tsconfig.json"), "{}").unwrap();
+        std::fs::write(src.join("index.ts"), "").unwrap();
+
+        let file = src.join("index.ts").to_string_lossy().into_owned();
+        let config = FileConfig::create(&[file]).unwrap().unwrap();
+        let path = config.path.clone();
+        let written: Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        drop(config);
+        let removed = !path.exists();
+        let root_dir = root.canonicalize().unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(path.parent(), Some(root_dir.as_path()));
+        assert_eq!(written["extends"], "./tsconfig.json");
+        assert!(written["files"][0].as_str().unwrap().ends_with("index.ts"));
+        assert_eq!(written["include"], json!([]));
+        assert!(removed);
+    }
+
+    #[test]
+    fn test_file_config_rejects_files_of_different_projects() {
+        let root =
+            std::env::temp_dir().join(format!("ts-analyzer-projects-{}", std::process::id()));
+        let mut files = Vec::new();
+        for package in ["app", "scripts"] {
+            let dir = root.join(package);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("tsconfig.json"), "{}").unwrap();
+            std::fs::write(dir.join("index.ts"), "").unwrap();
+            files.push(dir.join("index.ts").to_string_lossy().into_owned());
+        }
+
+        let result = FileConfig::create(&files);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let err = result.err().unwrap().to_string();
+        assert!(err.contains("belong to different projects"), "{}", err);
+    }
+}
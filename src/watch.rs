@@ -0,0 +1,167 @@
+use std::{
+    collections::HashMap,
+    io::{
+        BufRead,
+        BufReader,
+    },
+    process::{
+        Command,
+        Stdio,
+    },
+    sync::{
+        Arc,
+        Mutex,
+        PoisonError,
+    },
+};
+
+use anyhow::Context;
+
+use crate::{
+    error::{
+        TsError,
+        parser::Parser,
+        watch::{
+            self,
+            WatchEvent,
+        },
+    },
+    tsconfig::{
+        self,
+        FileConfig,
+    },
+};
+
+/// Run the checker in watch mode and call `on_cycle` with the diagnostics of every compilation.
+/// Returns when the checker exits, watching is usually stopped with Ctrl-C instead.
+pub fn run(
+    program: &str, args: &[String], mut parser: Parser, file_config: Option<FileConfig>,
+    mut on_cycle: impl FnMut(Vec<TsError>) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Could not run `{}`", program))?;
+    let stdout = child
+        .stdout
+        .take()
+        .context("stdout of the checker is not piped")?;
+
+    let child = Arc::new(Mutex::new(child));
+    tsconfig::remove_on_signal(file_config.as_ref(), Arc::clone(&child))?;
+
+    let mut diagnostics = Vec::new();
+    for line in BufReader::new(stdout).lines() {
+        let line = line?;
+        match watch::parse(&line) {
+            Some(WatchEvent::Starting) => {
+                parser.finish();
+                diagnostics.clear();
+            }
+            Some(WatchEvent::Finished) => {
+                diagnostics.extend(parser.finish());
+                on_cycle(std::mem::take(&mut diagnostics))?;
+            }
+            None => diagnostics.extend(parser.push(&line)),
+        }
+    }
+
+    // The checker stopped before it started watching, e.g. because of an invalid tsconfig
+    diagnostics.extend(parser.finish());
+    if !diagnostics.is_empty() {
+        on_cycle(diagnostics)?;
+    }
+
+    let status = child
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .wait()?;
+    anyhow::bail!("`{}` stopped watching ({})", program, status)
+}
+
+/// Compare a cycle with the previous one. Returns whether each diagnostic of `current` is new and
+/// the diagnostics of `previous` that were fixed.
+pub fn diff<'a>(previous: &'a [TsError], current: &[TsError]) -> (Vec<bool>, Vec<&'a TsError>) {
+    let mut remaining = counts(previous);
+    let new = current
+        .iter()
+        .map(|err| match remaining.get_mut(&key(err)) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .collect();
+
+    let mut remaining = counts(current);
+    let fixed = previous
+        .iter()
+        .filter(|err| match remaining.get_mut(&key(err)) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .collect();
+
+    (new, fixed)
+}
+
+/// Diagnostics are matched on file, code and message, their position moves with edits above them
+fn key(err: &TsError) -> (Option<&str>, String, &str) {
+    (err.file(), err.code.to_string(), &err.message)
+}
+
+fn counts(errors: &[TsError]) -> HashMap<(Option<&str>, String, &str), usize> {
+    let mut counts = HashMap::new();
+    for err in errors {
+        *counts.entry(key(err)).or_default() += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::codes::ErrorCode;
+
+    #[test]
+    fn test_diff_cycles() {
+        let unused = |line| {
+            TsError::new(
+                "src/a.ts",
+                line,
+                7,
+                ErrorCode::DeclaredButNeverUsed,
+                "'a' is declared but its value is never read.",
+            )
+        };
+        let missing = TsError::new(
+            "src/a.ts",
+            3,
+            1,
+            ErrorCode::Unsupported(2304),
+            "Cannot find name 'x'.",
+        );
+        let mismatch = TsError::new(
+            "src/b.ts",
+            1,
+            1,
+            ErrorCode::TypeMismatch,
+            "Type 'string' is not assignable to type 'number'.",
+        );
+
+        let previous = vec![unused(1), missing.clone()];
+        // A line was added above the unused variable
+        let current = vec![unused(2), mismatch];
+
+        let (new, fixed) = diff(&previous, &current);
+
+        assert_eq!(new, vec![false, true]);
+        assert_eq!(fixed.len(), 1);
+        assert_eq!(fixed[0].message, missing.message);
+    }
+}